@@ -0,0 +1,98 @@
+//! Derive macro for the `Pod` marker trait of [`safemem`](https://docs.rs/safemem).
+//!
+//! ```
+//! extern crate safemem;
+//! #[macro_use]
+//! extern crate safemem_derive;
+//!
+//! #[derive(Clone, Copy, Pod)]
+//! #[repr(C)]
+//! struct Header {
+//!     len: u32,
+//!     kind: u16,
+//!     flags: [u8; 2],
+//! }
+//! # fn main() {}
+//! ```
+
+extern crate proc_macro;
+extern crate proc_macro2;
+#[macro_use]
+extern crate quote;
+extern crate syn;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use syn::{Data, DeriveInput, Error, Meta, NestedMeta};
+
+/// Derive `safemem::Pod`, and with it `safemem::Zeroable`, for a struct.
+///
+/// The struct must
+/// * be `#[repr(C)]` or `#[repr(transparent)]`;
+/// * have no padding bytes, including trailing padding from e.g. `#[repr(align(N))]`;
+/// * only have fields which implement `Pod` themselves;
+/// * not be generic;
+/// * implement `Copy`, e.g. with `#[derive(Clone, Copy)]`.
+///
+/// Violating any of these is a compile error.
+#[proc_macro_derive(Pod)]
+pub fn derive_pod(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    expand_pod(&input).unwrap_or_else(|e| e.to_compile_error()).into()
+}
+
+fn expand_pod(input: &DeriveInput) -> Result<TokenStream2, Error> {
+    let fields = match input.data {
+        Data::Struct(ref data) => &data.fields,
+        _ => return Err(Error::new_spanned(&input.ident, "`Pod` can only be derived for structs")),
+    };
+
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(&input.generics, "`Pod` cannot be derived for generic structs"));
+    }
+
+    check_repr(input)?;
+
+    let name = &input.ident;
+    let types: Vec<_> = fields.iter().map(|field| &field.ty).collect();
+    let padding_msg = format!("`{}` has padding bytes, so it cannot derive `Pod`", name);
+
+    Ok(quote! {
+        const _: fn() = || {
+            fn assert_pod<T: ::safemem::Pod>() {}
+            #(assert_pod::<#types>();)*
+        };
+
+        // The size of a struct without padding is the sum of the sizes of its fields.
+        const _: () = assert!(
+            ::safemem::__private::size_of::<#name>()
+                == 0 #(+ ::safemem::__private::size_of::<#types>())*,
+            #padding_msg
+        );
+
+        unsafe impl ::safemem::Zeroable for #name {}
+        unsafe impl ::safemem::Pod for #name {}
+    })
+}
+
+/// Check that `input` has a `#[repr]` with a defined field layout.
+fn check_repr(input: &DeriveInput) -> Result<(), Error> {
+    for attr in &input.attrs {
+        if !attr.path.is_ident("repr") { continue; }
+
+        if let Meta::List(list) = attr.parse_meta()? {
+            for nested in &list.nested {
+                if let NestedMeta::Meta(Meta::Path(ref path)) = *nested {
+                    if path.is_ident("C") || path.is_ident("transparent") {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    }
+
+    Err(Error::new_spanned(
+        &input.ident,
+        "`Pod` can only be derived for `#[repr(C)]` or `#[repr(transparent)]` structs",
+    ))
+}
@@ -0,0 +1,12 @@
+extern crate trybuild;
+
+/// The `.stderr` snapshots match the diagnostics of one specific rustc, so this is only run on
+/// request with the toolchain they were generated with, as CI does:
+///
+/// `cargo +1.95.0 test -p safemem-derive -- --ignored`
+#[test]
+#[ignore]
+fn compile_fail() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,48 @@
+extern crate safemem;
+#[macro_use]
+extern crate safemem_derive;
+
+use safemem::{as_bytes, as_bytes_mut, cast_slice, write_bytes, Pod};
+
+#[derive(Clone, Copy, Debug, PartialEq, Pod)]
+#[repr(C)]
+struct Header {
+    len: u32,
+    kind: u16,
+    flags: [u8; 2],
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Pod)]
+#[repr(transparent)]
+struct Wrapper(u64);
+
+#[derive(Clone, Copy, Pod)]
+#[repr(C)]
+struct Packet {
+    header: Header,
+    payload: [Wrapper; 2],
+}
+
+#[derive(Clone, Copy, Pod)]
+#[repr(C)]
+struct Empty;
+
+fn assert_pod<T: Pod>() {}
+
+#[test]
+fn derived_pod() {
+    assert_pod::<Empty>();
+    assert_pod::<Packet>();
+
+    let mut headers = [Header { len: 1, kind: 2, flags: [3, 4] }; 2];
+    assert_eq!(as_bytes(&headers).len(), 16);
+
+    write_bytes(as_bytes_mut(&mut headers[1 ..]), 0);
+    assert_eq!(headers[1], Header { len: 0, kind: 0, flags: [0, 0] });
+
+    let words: &[u32] = cast_slice(&headers);
+    assert_eq!(words, &[1, words[1], 0, 0]);
+
+    let wrapped: &[Wrapper] = cast_slice(&[!0u64]);
+    assert_eq!(wrapped, &[Wrapper(!0)]);
+}
@@ -0,0 +1,11 @@
+extern crate safemem;
+#[macro_use]
+extern crate safemem_derive;
+
+#[derive(Clone, Copy, Pod)]
+#[repr(C)]
+struct Generic<T> {
+    a: T,
+}
+
+fn main() {}
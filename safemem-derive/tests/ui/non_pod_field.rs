@@ -0,0 +1,12 @@
+extern crate safemem;
+#[macro_use]
+extern crate safemem_derive;
+
+#[derive(Clone, Copy, Pod)]
+#[repr(C)]
+struct Flags {
+    a: bool,
+    b: [u8; 3],
+}
+
+fn main() {}
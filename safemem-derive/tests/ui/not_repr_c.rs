@@ -0,0 +1,11 @@
+extern crate safemem;
+#[macro_use]
+extern crate safemem_derive;
+
+#[derive(Clone, Copy, Pod)]
+struct Unspecified {
+    a: u32,
+    b: u32,
+}
+
+fn main() {}
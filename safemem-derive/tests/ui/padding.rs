@@ -0,0 +1,12 @@
+extern crate safemem;
+#[macro_use]
+extern crate safemem_derive;
+
+#[derive(Clone, Copy, Pod)]
+#[repr(C)]
+struct Padded {
+    a: u8,
+    b: u32,
+}
+
+fn main() {}
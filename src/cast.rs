@@ -0,0 +1,230 @@
+//! Safe conversions between plain old data and bytes.
+
+use std::{mem, ptr, slice};
+
+use marker::Pod;
+use Error;
+
+/// View the elements of `slice` as bytes.
+pub fn as_bytes<T: Pod>(slice: &[T]) -> &[u8] {
+    // `T: Pod` guarantees there are no uninitialized padding bytes.
+    unsafe { slice::from_raw_parts(slice.as_ptr() as *const u8, mem::size_of_val(slice)) }
+}
+
+/// View the elements of `slice` as mutable bytes.
+///
+/// This lets the byte-level functions of this crate, like [`write_bytes()`](fn.write_bytes.html),
+/// be applied to any `Pod` type.
+pub fn as_bytes_mut<T: Pod>(slice: &mut [T]) -> &mut [u8] {
+    // `T: Pod` guarantees any bytes written are a valid `T`.
+    unsafe { slice::from_raw_parts_mut(slice.as_mut_ptr() as *mut u8, mem::size_of_val(slice)) }
+}
+
+/// Reinterpret `slice` as a slice of `B`.
+///
+/// ### Panics
+/// * If `slice` is not aligned for `B`, unless it is empty.
+/// * If the size of `slice` in bytes is not a multiple of the size of `B`.
+pub fn cast_slice<A: Pod, B: Pod>(slice: &[A]) -> &[B] {
+    match try_cast_slice(slice) {
+        Ok(cast) => cast,
+        Err(e) => panic!("{}", e),
+    }
+}
+
+/// Reinterpret `slice` as a slice of `B`.
+///
+/// Non-panicking version of [`cast_slice()`](fn.cast_slice.html).
+///
+/// ### Errors
+/// Returns `Error::Misaligned` or `Error::SizeMismatch` under the same conditions that
+/// `cast_slice()` panics.
+pub fn try_cast_slice<A: Pod, B: Pod>(slice: &[A]) -> Result<&[B], Error> {
+    let len = cast_len::<A, B>(slice.as_ptr() as usize, slice.len())?;
+    if len == 0 { return Ok(&[]); }
+
+    Ok(unsafe { slice::from_raw_parts(slice.as_ptr() as *const B, len) })
+}
+
+/// Reinterpret `slice` as a mutable slice of `B`.
+///
+/// ### Panics
+/// * If `slice` is not aligned for `B`, unless it is empty.
+/// * If the size of `slice` in bytes is not a multiple of the size of `B`.
+pub fn cast_slice_mut<A: Pod, B: Pod>(slice: &mut [A]) -> &mut [B] {
+    match try_cast_slice_mut(slice) {
+        Ok(cast) => cast,
+        Err(e) => panic!("{}", e),
+    }
+}
+
+/// Reinterpret `slice` as a mutable slice of `B`.
+///
+/// Non-panicking version of [`cast_slice_mut()`](fn.cast_slice_mut.html).
+///
+/// ### Errors
+/// Returns `Error::Misaligned` or `Error::SizeMismatch` under the same conditions that
+/// `cast_slice_mut()` panics.
+pub fn try_cast_slice_mut<A: Pod, B: Pod>(slice: &mut [A]) -> Result<&mut [B], Error> {
+    let len = cast_len::<A, B>(slice.as_ptr() as usize, slice.len())?;
+    if len == 0 { return Ok(&mut []); }
+
+    Ok(unsafe { slice::from_raw_parts_mut(slice.as_mut_ptr() as *mut B, len) })
+}
+
+/// Read a `T` from `bytes` starting at `offset`, which need not be aligned for `T`.
+///
+/// ### Panics
+/// * If `offset` plus the size of `T` is out of bounds. The message refers to `offset` as
+///   `src_idx`.
+/// * If evaluating `offset` plus the size of `T` overflows.
+pub fn read_at<T: Pod>(bytes: &[u8], offset: usize) -> T {
+    match try_read_at(bytes, offset) {
+        Ok(value) => value,
+        Err(e) => panic!("{}", e),
+    }
+}
+
+/// Read a `T` from `bytes` starting at `offset`, which need not be aligned for `T`.
+///
+/// Non-panicking version of [`read_at()`](fn.read_at.html).
+///
+/// ### Errors
+/// Returns `Error::SrcOutOfBounds` or `Error::Overflow` under the same conditions that
+/// `read_at()` panics. `offset` is reported as the source index, so the error's `Display`
+/// message refers to it as `src_idx`.
+pub fn try_read_at<T: Pod>(bytes: &[u8], offset: usize) -> Result<T, Error> {
+    len_check!(bytes, offset, mem::size_of::<T>(), SrcOutOfBounds);
+
+    // `T: Pod` guarantees any bytes are a valid `T`.
+    Ok(unsafe { ptr::read_unaligned(bytes.as_ptr().add(offset) as *const T) })
+}
+
+/// Write `value` to `bytes` starting at `offset`, which need not be aligned for `T`.
+///
+/// ### Panics
+/// * If `offset` plus the size of `T` is out of bounds. The message refers to `offset` as
+///   `dest_idx`.
+/// * If evaluating `offset` plus the size of `T` overflows.
+pub fn write_at<T: Pod>(bytes: &mut [u8], offset: usize, value: T) {
+    unwrap_check!(try_write_at(bytes, offset, value));
+}
+
+/// Write `value` to `bytes` starting at `offset`, which need not be aligned for `T`.
+///
+/// Non-panicking version of [`write_at()`](fn.write_at.html).
+///
+/// ### Errors
+/// Returns `Error::DestOutOfBounds` or `Error::Overflow` under the same conditions that
+/// `write_at()` panics. `offset` is reported as the destination index, so the error's `Display`
+/// message refers to it as `dest_idx`. `bytes` is not modified in that case.
+pub fn try_write_at<T: Pod>(bytes: &mut [u8], offset: usize, value: T) -> Result<(), Error> {
+    len_check!(bytes, offset, mem::size_of::<T>(), DestOutOfBounds);
+
+    unsafe {
+        ptr::write_unaligned(bytes.as_mut_ptr().add(offset) as *mut T, value);
+    }
+
+    Ok(())
+}
+
+/// The length of a slice of `len` elements of `A` at `addr` when cast to `B`.
+fn cast_len<A, B>(addr: usize, len: usize) -> Result<usize, Error> {
+    // Cannot overflow as the slice already exists.
+    let bytes = len * mem::size_of::<A>();
+    let size = mem::size_of::<B>();
+
+    let new_len = if size == 0 {
+        if bytes != 0 || (len != 0 && mem::size_of::<A>() != 0) {
+            return Err(Error::SizeMismatch { len: bytes, size });
+        }
+        len
+    } else if bytes / size * size != bytes {
+        return Err(Error::SizeMismatch { len: bytes, size });
+    } else {
+        bytes / size
+    };
+
+    let align = mem::align_of::<B>();
+    // `align` is always a power of two.
+    if new_len != 0 && addr & (align - 1) != 0 {
+        return Err(Error::Misaligned { addr, align });
+    }
+
+    Ok(new_len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bytes_roundtrip() {
+        let mut arr = [0x0102_0304u32, 0x0506_0708];
+        assert_eq!(as_bytes(&arr).len(), 8);
+        assert_eq!(as_bytes(&arr)[..4], 0x0102_0304u32.to_ne_bytes());
+
+        ::write_bytes(as_bytes_mut(&mut arr), 0xFF);
+        assert_eq!(arr, [!0; 2]);
+        assert_eq!(as_bytes::<[u16; 0]>(&[[]; 3]), &[]);
+    }
+
+    #[test]
+    fn cast_checks() {
+        let arr = [1u16, 2, 3, 4, 5, 6];
+        let pairs: &[[u16; 2]] = cast_slice(&arr);
+        assert_eq!(pairs, &[[1, 2], [3, 4], [5, 6]]);
+
+        assert_eq!(
+            try_cast_slice::<u16, [u16; 4]>(&arr),
+            Err(Error::SizeMismatch { len: 12, size: 8 })
+        );
+        assert_eq!(try_cast_slice::<u16, ()>(&arr), Err(Error::SizeMismatch { len: 12, size: 0 }));
+        assert_eq!(try_cast_slice::<u16, u64>(&[]), Ok(&[][..]));
+        assert_eq!(cast_slice::<(), ()>(&[(); 3]).len(), 3);
+
+        let mut words = [0u64; 2];
+        let bytes = as_bytes_mut(&mut words);
+        let addr = bytes[1 ..].as_ptr() as usize;
+        assert_eq!(
+            try_cast_slice_mut::<u8, u32>(&mut bytes[1 .. 5]),
+            Err(Error::Misaligned { addr, align: 4 })
+        );
+        cast_slice_mut::<u8, u32>(&mut bytes[4 .. 12])[1] = !0;
+        assert_eq!(as_bytes(&words)[8 .. 12], [0xFF; 4]);
+    }
+
+    #[test]
+    #[should_panic(expected = "not a multiple")]
+    fn cast_size_mismatch() {
+        cast_slice::<u8, u16>(&[0; 3]);
+    }
+
+    #[test]
+    fn read_write_unaligned() {
+        let mut bytes = [0u8; 11];
+        write_at(&mut bytes, 1, 0x0102_0304u32);
+        write_at(&mut bytes, 5, [0xAAu16; 3]);
+        assert_eq!(read_at::<u32>(&bytes, 1), 0x0102_0304);
+        assert_eq!(read_at::<[u16; 3]>(&bytes, 5), [0xAA; 3]);
+        assert_eq!(bytes[1 .. 5], 0x0102_0304u32.to_ne_bytes());
+        assert_eq!(read_at::<()>(&bytes, 11), ());
+
+        assert_eq!(
+            try_read_at::<u64>(&bytes, 4),
+            Err(Error::SrcOutOfBounds { idx: 4, len: 8, slice_len: 11 })
+        );
+        assert_eq!(try_write_at(&mut bytes, !0, 0u16), Err(Error::Overflow { start: !0, len: 2 }));
+        assert_eq!(
+            try_write_at(&mut bytes, 10, 0u16),
+            Err(Error::DestOutOfBounds { idx: 10, len: 2, slice_len: 11 })
+        );
+        assert_eq!(read_at::<u16>(&bytes, 9), 0xAA);
+    }
+
+    #[test]
+    #[should_panic(expected = "Length 4 starting at 2 is out of bounds")]
+    fn read_at_bounds() {
+        read_at::<f32>(&[0; 5], 2);
+    }
+}
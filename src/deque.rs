@@ -0,0 +1,133 @@
+//! Bulk insertion and removal for `VecDeque<T: Copy>`.
+//!
+//! These are the `VecDeque` counterparts of [`prepend()`](../fn.prepend.html) and friends,
+//! taking and returning whole slices at a time instead of single elements.
+
+use std::cmp;
+
+use alloc::collections::VecDeque;
+
+use {check_capacity, Error};
+
+/// Prepend `elems` to `deque`, resizing if necessary.
+///
+/// The elements are appended as a slice and then rotated into place, which moves at most
+/// `elems.len()` elements.
+///
+/// ### Panics
+/// If the new capacity overflows.
+pub fn prepend<T: Copy>(deque: &mut VecDeque<T>, elems: &[T]) {
+    append(deque, elems);
+    deque.rotate_right(elems.len());
+}
+
+/// Prepend `elems` to `deque`, resizing if necessary.
+///
+/// Non-panicking version of [`prepend()`](fn.prepend.html).
+///
+/// ### Errors
+/// If the new capacity overflows or allocating it fails. The `VecDeque` is not modified in that
+/// case.
+///
+/// Without the `try_reserve` feature, allocation failure aborts the process instead.
+pub fn try_prepend<T: Copy>(deque: &mut VecDeque<T>, elems: &[T]) -> Result<(), Error> {
+    try_append(deque, elems)?;
+    deque.rotate_right(elems.len());
+    Ok(())
+}
+
+/// Append `elems` to `deque`, resizing if necessary.
+///
+/// ### Panics
+/// If the new capacity overflows.
+pub fn append<T: Copy>(deque: &mut VecDeque<T>, elems: &[T]) {
+    // Newer versions of `std` specialize `Extend<&T>` to copy slices in bulk; older ones push
+    // the elements one by one.
+    deque.extend(elems);
+}
+
+/// Append `elems` to `deque`, resizing if necessary.
+///
+/// Non-panicking version of [`append()`](fn.append.html).
+///
+/// ### Errors
+/// If the new capacity overflows or allocating it fails. The `VecDeque` is not modified in that
+/// case.
+///
+/// Without the `try_reserve` feature, allocation failure aborts the process instead.
+pub fn try_append<T: Copy>(deque: &mut VecDeque<T>, elems: &[T]) -> Result<(), Error> {
+    let (len, additional) = (deque.len(), elems.len());
+    check_capacity::<T>(len, additional)?;
+    #[cfg(feature = "try_reserve")]
+    deque.try_reserve(additional).map_err(|_| Error::AllocFailed { len, additional })?;
+    #[cfg(not(feature = "try_reserve"))]
+    deque.reserve(additional);
+
+    // Newer versions of `std` specialize `Extend<&T>` to copy slices in bulk; older ones push
+    // the elements one by one.
+    deque.extend(elems);
+    Ok(())
+}
+
+/// Move as many elements from the front of `deque` as fit into `dest`, returning the number
+/// moved.
+///
+/// The elements are copied out of the (at most two) contiguous parts of `deque` with
+/// `copy_from_slice()`.
+pub fn drain_front_into<T: Copy>(deque: &mut VecDeque<T>, dest: &mut [T]) -> usize {
+    let count = cmp::min(dest.len(), deque.len());
+
+    {
+        let (first, second) = deque.as_slices();
+        let first_len = cmp::min(count, first.len());
+        dest[.. first_len].copy_from_slice(&first[.. first_len]);
+        dest[first_len .. count].copy_from_slice(&second[.. count - first_len]);
+    }
+
+    // `T: Copy` so nothing is dropped; this only moves the head.
+    deque.drain(.. count);
+
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prepend_append() {
+        let mut deque: VecDeque<i32> = VecDeque::with_capacity(4);
+        deque.push_back(3);
+        deque.push_front(2);
+
+        prepend(&mut deque, &[0, 1]);
+        append(&mut deque, &[4, 5, 6]);
+        prepend(&mut deque, &[]);
+        assert_eq!(deque, [0, 1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn try_prepend_append() {
+        let mut deque: VecDeque<u16> = VecDeque::new();
+        assert_eq!(try_prepend(&mut deque, &[1, 2]), Ok(()));
+        assert_eq!(try_append(&mut deque, &[3]), Ok(()));
+        assert_eq!(deque, [1, 2, 3]);
+    }
+
+    #[test]
+    fn drain_front() {
+        let mut deque: VecDeque<u8> = VecDeque::with_capacity(8);
+        append(&mut deque, b"xxxxxx");
+        deque.drain(.. 6);
+        // Force the contents to wrap around.
+        append(&mut deque, b"abcdef");
+        prepend(&mut deque, b"01");
+
+        let mut out = [0u8; 5];
+        assert_eq!(drain_front_into(&mut deque, &mut out), 5);
+        assert_eq!(&out, b"01abc");
+        assert_eq!(drain_front_into(&mut deque, &mut out), 3);
+        assert_eq!(&out[.. 3], b"def");
+        assert!(deque.is_empty());
+    }
+}
@@ -0,0 +1,225 @@
+//! Byte order conversion of integer slices.
+//!
+//! The conversions are simple loops over the elements, which the compiler vectorizes into
+//! byte shuffles on targets that support them.
+
+use std::mem;
+
+use cast::{as_bytes, as_bytes_mut};
+use marker::Pod;
+use Error;
+
+/// A byte order.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Endian {
+    /// Most significant byte first.
+    Big,
+    /// Least significant byte first.
+    Little,
+}
+
+impl Endian {
+    /// The byte order of the target.
+    #[cfg(target_endian = "big")]
+    pub const NATIVE: Endian = Endian::Big;
+    /// The byte order of the target.
+    #[cfg(target_endian = "little")]
+    pub const NATIVE: Endian = Endian::Little;
+}
+
+/// Integer types whose byte order can be reversed.
+///
+/// This trait is sealed and cannot be implemented outside of this crate.
+pub trait SwapBytes: Pod + private::Sealed {
+    /// Reverse the byte order of `self`.
+    fn swap_bytes(self) -> Self;
+}
+
+macro_rules! impl_swap_bytes (
+    ($($ty:ty),*) => {
+        $(impl private::Sealed for $ty {}
+
+        impl SwapBytes for $ty {
+            fn swap_bytes(self) -> Self {
+                <$ty>::swap_bytes(self)
+            }
+        })*
+    }
+);
+
+impl_swap_bytes!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+mod private {
+    /// Keeps `SwapBytes` to the integer types, so `elem_count()` never divides by zero.
+    pub trait Sealed {}
+}
+
+/// Reverse the byte order of every element of `slice`.
+pub fn swap_bytes_in_place<T: SwapBytes>(slice: &mut [T]) {
+    for elem in slice.iter_mut() {
+        *elem = elem.swap_bytes();
+    }
+}
+
+/// Convert every element of `slice` from native to `endian` byte order, or vice versa.
+pub fn to_endian_in_place<T: SwapBytes>(slice: &mut [T], endian: Endian) {
+    if endian != Endian::NATIVE {
+        swap_bytes_in_place(slice);
+    }
+}
+
+/// Convert every element of `slice` from native to big endian byte order.
+///
+/// As this is its own inverse, it also converts from big endian to native byte order.
+pub fn to_be_in_place<T: SwapBytes>(slice: &mut [T]) {
+    to_endian_in_place(slice, Endian::Big);
+}
+
+/// Convert every element of `slice` from native to little endian byte order.
+///
+/// As this is its own inverse, it also converts from little endian to native byte order.
+pub fn to_le_in_place<T: SwapBytes>(slice: &mut [T]) {
+    to_endian_in_place(slice, Endian::Little);
+}
+
+/// Decode the integers in `endian` byte order in `src` into the start of `dest`, returning the
+/// number of integers decoded.
+///
+/// ### Panics
+/// * If `src.len()` is not a multiple of the size of `T`.
+/// * If `dest` is too short to hold the decoded integers.
+pub fn decode_from_bytes<T: SwapBytes>(dest: &mut [T], src: &[u8], endian: Endian) -> usize {
+    match try_decode_from_bytes(dest, src, endian) {
+        Ok(count) => count,
+        Err(e) => panic!("{}", e),
+    }
+}
+
+/// Decode the integers in `endian` byte order in `src` into the start of `dest`, returning the
+/// number of integers decoded.
+///
+/// Non-panicking version of [`decode_from_bytes()`](fn.decode_from_bytes.html).
+///
+/// ### Errors
+/// Returns `Error::SizeMismatch` or `Error::DestOutOfBounds` under the same conditions that
+/// `decode_from_bytes()` panics. `dest` is not modified in that case.
+pub fn try_decode_from_bytes<T: SwapBytes>(dest: &mut [T], src: &[u8], endian: Endian)
+    -> Result<usize, Error> {
+    let count = elem_count::<T>(src.len())?;
+    if count > dest.len() {
+        return Err(Error::DestOutOfBounds { idx: 0, len: count, slice_len: dest.len() });
+    }
+
+    let dest = &mut dest[.. count];
+    as_bytes_mut(dest).copy_from_slice(src);
+    to_endian_in_place(dest, endian);
+
+    Ok(count)
+}
+
+/// Encode the integers in `src` in `endian` byte order into the start of `dest`, returning the
+/// number of bytes written.
+///
+/// ### Panics
+/// If `dest` is too short to hold the encoded integers.
+pub fn encode_to_bytes<T: SwapBytes>(dest: &mut [u8], src: &[T], endian: Endian) -> usize {
+    match try_encode_to_bytes(dest, src, endian) {
+        Ok(len) => len,
+        Err(e) => panic!("{}", e),
+    }
+}
+
+/// Encode the integers in `src` in `endian` byte order into the start of `dest`, returning the
+/// number of bytes written.
+///
+/// Non-panicking version of [`encode_to_bytes()`](fn.encode_to_bytes.html).
+///
+/// ### Errors
+/// Returns `Error::DestOutOfBounds` under the same conditions that `encode_to_bytes()` panics.
+/// `dest` is not modified in that case.
+pub fn try_encode_to_bytes<T: SwapBytes>(dest: &mut [u8], src: &[T], endian: Endian)
+    -> Result<usize, Error> {
+    let len = mem::size_of_val(src);
+    if len > dest.len() {
+        return Err(Error::DestOutOfBounds { idx: 0, len, slice_len: dest.len() });
+    }
+
+    // Convert one chunk at a time in a buffer of `T` to keep the swaps vectorizable.
+    // `T: Zeroable` guarantees this is a valid value.
+    let mut buf = [unsafe { mem::zeroed::<T>() }; 64];
+    let size = mem::size_of::<T>();
+
+    for (chunk, dest) in src.chunks(buf.len()).zip(dest[.. len].chunks_mut(buf.len() * size)) {
+        let buf = &mut buf[.. chunk.len()];
+        buf.copy_from_slice(chunk);
+        to_endian_in_place(buf, endian);
+        dest.copy_from_slice(as_bytes(buf));
+    }
+
+    Ok(len)
+}
+
+/// The number of `T` in `len` bytes.
+fn elem_count<T>(len: usize) -> Result<usize, Error> {
+    let size = mem::size_of::<T>();
+    // `SwapBytes` is sealed and only implemented for integers, which are never zero-sized.
+    let count = len / size;
+
+    if count * size != len {
+        return Err(Error::SizeMismatch { len, size });
+    }
+
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn swap_in_place() {
+        let mut arr = [0x0102u16, 0x0304, 0xA0B0];
+        swap_bytes_in_place(&mut arr);
+        assert_eq!(arr, [0x0201, 0x0403, 0xB0A0]);
+
+        let mut words = [0x0102_0304u32; 3];
+        to_be_in_place(&mut words);
+        assert_eq!(as_bytes(&words)[.. 4], [1, 2, 3, 4]);
+        to_be_in_place(&mut words);
+        to_le_in_place(&mut words);
+        assert_eq!(as_bytes(&words)[8 ..], [4, 3, 2, 1]);
+    }
+
+    #[test]
+    fn decode_encode() {
+        let bytes = [0u8, 1, 0, 2, 0, 3, 0, 4];
+        let mut dest = [0u16; 5];
+        assert_eq!(decode_from_bytes(&mut dest, &bytes, Endian::Big), 4);
+        assert_eq!(dest, [1, 2, 3, 4, 0]);
+        assert_eq!(decode_from_bytes(&mut dest, &bytes[8 ..], Endian::Little), 0);
+
+        let mut out = [0u8; 9];
+        assert_eq!(encode_to_bytes(&mut out, &dest[.. 4], Endian::Big), 8);
+        assert_eq!(out[.. 8], bytes);
+
+        let mut longs = [0u64; 100];
+        longs[99] = 0x0102;
+        let mut long_bytes = [0u8; 800];
+        encode_to_bytes(&mut long_bytes, &longs, Endian::Little);
+        assert_eq!(long_bytes[792 .. 794], [2, 1]);
+
+        assert_eq!(
+            try_decode_from_bytes(&mut dest, &bytes[.. 7], Endian::Big),
+            Err(Error::SizeMismatch { len: 7, size: 2 })
+        );
+        assert_eq!(
+            try_decode_from_bytes(&mut dest[.. 3], &bytes, Endian::Big),
+            Err(Error::DestOutOfBounds { idx: 0, len: 4, slice_len: 3 })
+        );
+        assert_eq!(
+            try_encode_to_bytes(&mut out[.. 7], &dest[.. 4], Endian::Little),
+            Err(Error::DestOutOfBounds { idx: 0, len: 8, slice_len: 7 })
+        );
+        assert_eq!(dest, [1, 2, 3, 4, 0]);
+    }
+}
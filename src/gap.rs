@@ -0,0 +1,275 @@
+//! A gap buffer for efficient insertion and removal at a cursor.
+
+use std::{cmp, fmt};
+
+use alloc::vec::Vec;
+
+use {copy_over, reserve, Error, Fallibility};
+use Fallibility::*;
+
+/// A growable buffer of `T: Copy` with a movable gap of spare elements at the cursor.
+///
+/// Inserting and removing at the cursor only touches the elements being inserted or removed.
+/// Moving the cursor moves the gap with it, which is a single [`copy_over()`](fn.copy_over.html)
+/// of the elements between the old and new cursor positions, making it cheap to edit near the
+/// previous edit, as text editors do.
+///
+/// The elements before and after the cursor are accessible as a pair of slices via
+/// [`as_slices()`](#method.as_slices).
+#[derive(Clone)]
+pub struct GapBuffer<T: Copy> {
+    /// All elements of `buf` are initialized; the contents of the gap are unspecified.
+    buf: Vec<T>,
+    gap_start: usize,
+    gap_end: usize,
+}
+
+impl<T: Copy> GapBuffer<T> {
+    /// Create an empty buffer without allocating.
+    pub fn new() -> Self {
+        GapBuffer {
+            buf: Vec::new(),
+            gap_start: 0,
+            gap_end: 0,
+        }
+    }
+
+    /// The number of elements in the buffer, excluding the gap.
+    pub fn len(&self) -> usize {
+        self.buf.len() - self.gap_len()
+    }
+
+    /// `true` if there are no elements in the buffer.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The position of the cursor, i.e. the number of elements before it.
+    pub fn cursor(&self) -> usize {
+        self.gap_start
+    }
+
+    /// The number of elements that can be inserted at the cursor without reallocating.
+    pub fn gap_len(&self) -> usize {
+        self.gap_end - self.gap_start
+    }
+
+    /// Move the cursor to `pos`, moving the elements between the old and new positions to the
+    /// other side of the gap.
+    ///
+    /// ### Panics
+    /// If `pos > self.len()`.
+    pub fn set_cursor(&mut self, pos: usize) {
+        assert!(pos <= self.len(), "`pos` ({}) out of bounds. Length: {}", pos, self.len());
+
+        let gap_len = self.gap_len();
+
+        if pos < self.gap_start {
+            copy_over(&mut self.buf, pos, pos + gap_len, self.gap_start - pos);
+        } else if pos > self.gap_start {
+            copy_over(&mut self.buf, self.gap_end, self.gap_start, pos - self.gap_start);
+        }
+
+        self.gap_start = pos;
+        self.gap_end = pos + gap_len;
+    }
+
+    /// Insert `elems` at the cursor, leaving the cursor after them.
+    ///
+    /// ### Panics
+    /// If the new capacity overflows.
+    pub fn insert_slice(&mut self, elems: &[T]) {
+        unwrap_check!(self.insert_slice_impl(elems, Infallible));
+    }
+
+    /// Insert `elems` at the cursor, leaving the cursor after them.
+    ///
+    /// Non-panicking version of [`insert_slice()`](#method.insert_slice).
+    ///
+    /// ### Errors
+    /// If the new capacity overflows or allocating it fails. The buffer is not modified in that
+    /// case.
+    ///
+    /// Without the `try_reserve` feature, allocation failure aborts the process instead.
+    pub fn try_insert_slice(&mut self, elems: &[T]) -> Result<(), Error> {
+        self.insert_slice_impl(elems, Fallible)
+    }
+
+    fn insert_slice_impl(&mut self, elems: &[T], fallibility: Fallibility) -> Result<(), Error> {
+        if elems.is_empty() { return Ok(()); }
+
+        self.reserve(elems.len(), elems[0], fallibility)?;
+
+        let end = self.gap_start + elems.len();
+        self.buf[self.gap_start .. end].copy_from_slice(elems);
+        self.gap_start = end;
+
+        Ok(())
+    }
+
+    /// Insert `elem` at the cursor, leaving the cursor after it.
+    ///
+    /// ### Panics
+    /// If the new capacity overflows.
+    pub fn insert(&mut self, elem: T) {
+        self.insert_slice(&[elem]);
+    }
+
+    /// Remove the `n` elements before the cursor.
+    ///
+    /// ### Panics
+    /// If there are fewer than `n` elements before the cursor.
+    pub fn remove_before(&mut self, n: usize) {
+        assert!(n <= self.gap_start, "Cannot remove {} elements before cursor at {}",
+                n, self.gap_start);
+        self.gap_start -= n;
+    }
+
+    /// Remove the `n` elements after the cursor.
+    ///
+    /// ### Panics
+    /// If there are fewer than `n` elements after the cursor.
+    pub fn remove_after(&mut self, n: usize) {
+        let after = self.buf.len() - self.gap_end;
+        assert!(n <= after, "Cannot remove {} elements after cursor ({} elements after it)",
+                n, after);
+        self.gap_end += n;
+    }
+
+    /// Remove all elements, keeping the allocation.
+    pub fn clear(&mut self) {
+        self.gap_start = 0;
+        self.gap_end = self.buf.len();
+    }
+
+    /// The elements before and after the cursor, respectively.
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        (&self.buf[.. self.gap_start], &self.buf[self.gap_end ..])
+    }
+
+    /// The elements before and after the cursor, respectively.
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        let (before, rest) = self.buf.split_at_mut(self.gap_start);
+        (before, &mut rest[self.gap_end - self.gap_start ..])
+    }
+
+    /// Convert the buffer into a `Vec`, closing the gap.
+    pub fn into_vec(mut self) -> Vec<T> {
+        let len = self.len();
+        self.set_cursor(len);
+        self.buf.truncate(len);
+        self.buf
+    }
+
+    /// Ensure the gap can hold at least `additional` elements, growing it by at least the
+    /// current length. `filler` is used to initialize the new part of the gap.
+    fn reserve(&mut self, additional: usize, filler: T, fallibility: Fallibility)
+        -> Result<(), Error> {
+        let gap_len = self.gap_len();
+        if additional <= gap_len { return Ok(()); }
+
+        let grow_by = cmp::max(additional - gap_len, self.len());
+        reserve(&mut self.buf, grow_by, fallibility)
+            .map_err(|e| e.for_collection(self.len(), additional))?;
+
+        let old_len = self.buf.len();
+        // Does not reallocate as we just reserved the capacity for it.
+        self.buf.resize(old_len + grow_by, filler);
+
+        // Move the elements after the gap to the new end.
+        if self.gap_end < old_len {
+            copy_over(&mut self.buf, self.gap_end, self.gap_end + grow_by, old_len - self.gap_end);
+        }
+
+        self.gap_end += grow_by;
+
+        Ok(())
+    }
+}
+
+impl<T: Copy> Default for GapBuffer<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Copy> From<Vec<T>> for GapBuffer<T> {
+    /// Convert `vec` into a gap buffer with the cursor at the end.
+    fn from(vec: Vec<T>) -> Self {
+        let len = vec.len();
+        GapBuffer {
+            buf: vec,
+            gap_start: len,
+            gap_end: len,
+        }
+    }
+}
+
+impl<T: Copy + fmt::Debug> fmt::Debug for GapBuffer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (before, after) = self.as_slices();
+        f.debug_list().entries(before.iter().chain(after)).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_move_remove() {
+        let mut buf = GapBuffer::new();
+        buf.insert_slice(b"hello world");
+        assert_eq!(buf.cursor(), 11);
+
+        buf.set_cursor(5);
+        buf.insert(b',');
+        assert_eq!(buf.as_slices(), (&b"hello,"[..], &b" world"[..]));
+
+        buf.remove_after(6);
+        buf.insert_slice(b" there");
+        buf.set_cursor(0);
+        buf.remove_before(0);
+        buf.remove_after(1);
+        buf.insert(b'j');
+        assert_eq!(buf.len(), 12);
+        assert_eq!(buf.into_vec(), b"jello, there");
+    }
+
+    #[test]
+    fn from_vec() {
+        let mut buf = GapBuffer::from(vec![1, 2, 3, 4]);
+        buf.set_cursor(2);
+        buf.insert_slice(&[5, 6, 7]);
+        buf.set_cursor(7);
+        buf.remove_before(2);
+        buf.as_mut_slices().0[0] = 0;
+        assert_eq!(buf.into_vec(), &[0, 2, 5, 6, 7]);
+    }
+
+    #[test]
+    fn try_insert() {
+        let mut buf = GapBuffer::from(vec![0u64; 2]);
+        assert_eq!(buf.try_insert_slice(&[1; 8]), Ok(()));
+        assert_eq!(buf.as_slices(), (&[0, 0, 1, 1, 1, 1, 1, 1, 1, 1][..], &[][..]));
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn cursor_bounds() {
+        let mut buf = GapBuffer::from(vec![1, 2, 3]);
+        buf.remove_before(1);
+        buf.set_cursor(3);
+    }
+
+    /// Detect potential uninit values when running miri
+    #[test]
+    #[cfg(miri)]
+    fn insert_bool() {
+        let mut buf = GapBuffer::from(vec![false, false]);
+        buf.set_cursor(1);
+        buf.insert_slice(&[true, true, true]);
+        buf.set_cursor(0);
+        buf.into_vec();
+    }
+}
@@ -0,0 +1,408 @@
+//! A `Vec`-like buffer with spare capacity at both ends.
+
+use std::ops::{Deref, DerefMut};
+use std::{cmp, fmt, mem, ptr, slice};
+
+use alloc::vec::Vec;
+
+use {reserve, Error, Fallibility};
+use Fallibility::*;
+
+/// A growable buffer of `T: Copy` which keeps spare capacity at the front ("headroom") as well as
+/// at the back ("tailroom").
+///
+/// Unlike [`prepend()`](fn.prepend.html), which moves every element of the `Vec` on each call,
+/// prepending to a `HeadroomVec` only copies the new elements as long as there is enough
+/// headroom. When there is not, the buffer is reallocated with the headroom grown by at least the
+/// current length, making `push_front_slice()` amortized `O(elems.len())` just like
+/// `push_back_slice()`. This makes it suitable for e.g. building network packets from the
+/// innermost layer outwards.
+///
+/// The minimum extra room to reserve on each side when reallocating is configurable with
+/// [`set_growth()`](#method.set_growth).
+pub struct HeadroomVec<T: Copy> {
+    /// Only used for its allocation; its length is always zero.
+    buf: Vec<T>,
+    /// The index of the first element in `buf`.
+    head: usize,
+    len: usize,
+    front_growth: usize,
+    back_growth: usize,
+}
+
+impl<T: Copy> HeadroomVec<T> {
+    /// Create an empty buffer without allocating.
+    pub fn new() -> Self {
+        Self::with_capacity(0, 0)
+    }
+
+    /// Create an empty buffer with at least `headroom` spare elements at the front and
+    /// `tailroom` spare elements at the back.
+    ///
+    /// ### Panics
+    /// If `headroom + tailroom` overflows.
+    pub fn with_capacity(headroom: usize, tailroom: usize) -> Self {
+        HeadroomVec {
+            buf: Vec::with_capacity(headroom.checked_add(tailroom).expect("capacity overflow")),
+            head: headroom,
+            len: 0,
+            front_growth: 0,
+            back_growth: 0,
+        }
+    }
+
+    /// Set the minimum number of spare elements to reserve at the front and back respectively
+    /// whenever the buffer is reallocated, in addition to the room that was requested.
+    ///
+    /// Regardless of this setting, the side which ran out of room is always grown by at least
+    /// the current length of the buffer.
+    pub fn set_growth(&mut self, front: usize, back: usize) {
+        self.front_growth = front;
+        self.back_growth = back;
+    }
+
+    /// The number of elements that can be prepended without reallocating.
+    pub fn headroom(&self) -> usize {
+        self.head
+    }
+
+    /// The number of elements that can be appended without reallocating.
+    pub fn tailroom(&self) -> usize {
+        self.buf.capacity() - self.head - self.len
+    }
+
+    /// Ensure there is room to prepend at least `additional` elements without reallocating.
+    ///
+    /// ### Panics
+    /// If the new capacity overflows.
+    pub fn reserve_front(&mut self, additional: usize) {
+        unwrap_check!(self.reserve_front_impl(additional, Infallible));
+    }
+
+    /// Ensure there is room to prepend at least `additional` elements without reallocating.
+    ///
+    /// Non-panicking version of [`reserve_front()`](#method.reserve_front).
+    ///
+    /// ### Errors
+    /// If the new capacity overflows or allocating it fails. The buffer is not modified in that
+    /// case.
+    ///
+    /// Without the `try_reserve` feature, allocation failure aborts the process instead.
+    pub fn try_reserve_front(&mut self, additional: usize) -> Result<(), Error> {
+        self.reserve_front_impl(additional, Fallible)
+    }
+
+    fn reserve_front_impl(&mut self, additional: usize, fallibility: Fallibility)
+        -> Result<(), Error> {
+        if additional <= self.headroom() { return Ok(()); }
+
+        if mem::size_of::<T>() == 0 {
+            return self.try_move_head(additional, 0, additional);
+        }
+
+        let front = additional.checked_add(cmp::max(self.len, self.front_growth))
+            .ok_or(Error::CapacityOverflow { len: self.len, additional })?;
+        let back = cmp::max(self.tailroom(), self.back_growth);
+        self.realloc(front, back, additional, fallibility)
+    }
+
+    /// Ensure there is room to append at least `additional` elements without reallocating.
+    ///
+    /// ### Panics
+    /// If the new capacity overflows.
+    pub fn reserve_back(&mut self, additional: usize) {
+        unwrap_check!(self.reserve_back_impl(additional, Infallible));
+    }
+
+    /// Ensure there is room to append at least `additional` elements without reallocating.
+    ///
+    /// Non-panicking version of [`reserve_back()`](#method.reserve_back).
+    ///
+    /// ### Errors
+    /// If the new capacity overflows or allocating it fails. The buffer is not modified in that
+    /// case.
+    ///
+    /// Without the `try_reserve` feature, allocation failure aborts the process instead.
+    pub fn try_reserve_back(&mut self, additional: usize) -> Result<(), Error> {
+        self.reserve_back_impl(additional, Fallible)
+    }
+
+    fn reserve_back_impl(&mut self, additional: usize, fallibility: Fallibility)
+        -> Result<(), Error> {
+        if additional <= self.tailroom() { return Ok(()); }
+
+        if mem::size_of::<T>() == 0 {
+            return self.try_move_head(0, additional, additional);
+        }
+
+        let front = cmp::max(self.headroom(), self.front_growth);
+        let back = additional.checked_add(cmp::max(self.len, self.back_growth))
+            .ok_or(Error::CapacityOverflow { len: self.len, additional })?;
+        self.realloc(front, back, additional, fallibility)
+    }
+
+    /// Copy `elems` to the front of the buffer, reallocating if there is not enough headroom.
+    ///
+    /// ### Panics
+    /// If the new capacity overflows.
+    pub fn push_front_slice(&mut self, elems: &[T]) {
+        unwrap_check!(self.push_front_slice_impl(elems, Infallible));
+    }
+
+    /// Copy `elems` to the front of the buffer, reallocating if there is not enough headroom.
+    ///
+    /// Non-panicking version of [`push_front_slice()`](#method.push_front_slice).
+    ///
+    /// ### Errors
+    /// If the new capacity overflows or allocating it fails. The buffer is not modified in that
+    /// case.
+    ///
+    /// Without the `try_reserve` feature, allocation failure aborts the process instead.
+    pub fn try_push_front_slice(&mut self, elems: &[T]) -> Result<(), Error> {
+        self.push_front_slice_impl(elems, Fallible)
+    }
+
+    fn push_front_slice_impl(&mut self, elems: &[T], fallibility: Fallibility)
+        -> Result<(), Error> {
+        self.reserve_front_impl(elems.len(), fallibility)?;
+
+        self.head -= elems.len();
+        unsafe {
+            ptr::copy_nonoverlapping(elems.as_ptr(), self.as_mut_ptr(), elems.len());
+        }
+        // Set the len *after* having initialized the elements.
+        self.len += elems.len();
+
+        Ok(())
+    }
+
+    /// Copy `elems` to the back of the buffer, reallocating if there is not enough tailroom.
+    ///
+    /// ### Panics
+    /// If the new capacity overflows.
+    pub fn push_back_slice(&mut self, elems: &[T]) {
+        unwrap_check!(self.push_back_slice_impl(elems, Infallible));
+    }
+
+    /// Copy `elems` to the back of the buffer, reallocating if there is not enough tailroom.
+    ///
+    /// Non-panicking version of [`push_back_slice()`](#method.push_back_slice).
+    ///
+    /// ### Errors
+    /// If the new capacity overflows or allocating it fails. The buffer is not modified in that
+    /// case.
+    ///
+    /// Without the `try_reserve` feature, allocation failure aborts the process instead.
+    pub fn try_push_back_slice(&mut self, elems: &[T]) -> Result<(), Error> {
+        self.push_back_slice_impl(elems, Fallible)
+    }
+
+    fn push_back_slice_impl(&mut self, elems: &[T], fallibility: Fallibility)
+        -> Result<(), Error> {
+        self.reserve_back_impl(elems.len(), fallibility)?;
+
+        unsafe {
+            ptr::copy_nonoverlapping(elems.as_ptr(), self.as_mut_ptr().add(self.len), elems.len());
+        }
+        // Set the len *after* having initialized the elements.
+        self.len += elems.len();
+
+        Ok(())
+    }
+
+    /// Remove all elements, keeping the allocation and the headroom.
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Convert the buffer into a `Vec`, moving the elements to the start of the allocation if
+    /// there is any headroom.
+    pub fn into_vec(self) -> Vec<T> {
+        let mut buf = self.buf;
+        unsafe {
+            let ptr = buf.as_mut_ptr();
+            ptr::copy(ptr.add(self.head), ptr, self.len);
+            buf.set_len(self.len);
+        }
+        buf
+    }
+
+    fn as_mut_ptr(&mut self) -> *mut T {
+        unsafe { self.buf.as_mut_ptr().add(self.head) }
+    }
+
+    /// Zero-sized elements take no memory and `buf` already has a capacity of `usize::MAX`, so
+    /// instead of reallocating, only move `head` to leave `front` elements of headroom, provided
+    /// that it and `back` elements of tailroom fit in the capacity. `additional` is only used for
+    /// error reporting.
+    fn try_move_head(&mut self, front: usize, back: usize, additional: usize) -> Result<(), Error> {
+        front.checked_add(self.len)
+            .and_then(|end| end.checked_add(back))
+            .ok_or(Error::CapacityOverflow { len: self.len, additional })?;
+
+        self.head = front;
+
+        Ok(())
+    }
+
+    /// Move the elements to a new allocation with exactly `front` elements of headroom and at
+    /// least `back` elements of tailroom. `additional` is only used for error reporting.
+    fn realloc(&mut self, front: usize, back: usize, additional: usize, fallibility: Fallibility)
+        -> Result<(), Error> {
+        let cap = front.checked_add(self.len)
+            .and_then(|cap| cap.checked_add(back))
+            .ok_or(Error::CapacityOverflow { len: self.len, additional })?;
+
+        let mut buf: Vec<T> = Vec::new();
+        reserve(&mut buf, cap, fallibility).map_err(|e| e.for_collection(self.len, additional))?;
+
+        unsafe {
+            ptr::copy_nonoverlapping(self.as_mut_ptr(), buf.as_mut_ptr().add(front), self.len);
+        }
+        self.buf = buf;
+        self.head = front;
+
+        Ok(())
+    }
+}
+
+impl<T: Copy> Default for HeadroomVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Copy> Deref for HeadroomVec<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.buf.as_ptr().add(self.head), self.len) }
+    }
+}
+
+impl<T: Copy> DerefMut for HeadroomVec<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.as_mut_ptr(), self.len) }
+    }
+}
+
+impl<T: Copy> From<Vec<T>> for HeadroomVec<T> {
+    fn from(mut vec: Vec<T>) -> Self {
+        let len = vec.len();
+        // `T: Copy` so nothing needs to be dropped; the elements stay in the allocation.
+        unsafe {
+            vec.set_len(0);
+        }
+
+        HeadroomVec {
+            buf: vec,
+            head: 0,
+            len,
+            front_growth: 0,
+            back_growth: 0,
+        }
+    }
+}
+
+impl<T: Copy> Clone for HeadroomVec<T> {
+    fn clone(&self) -> Self {
+        let mut clone = Self::with_capacity(self.headroom(), self.len + self.tailroom());
+        clone.set_growth(self.front_growth, self.back_growth);
+        clone.push_back_slice(self);
+        clone
+    }
+}
+
+impl<T: Copy + fmt::Debug> fmt::Debug for HeadroomVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_front_back() {
+        let mut buf = HeadroomVec::new();
+        buf.push_back_slice(b"payload");
+        buf.push_front_slice(b"udp:");
+        buf.push_front_slice(b"ip:");
+        buf.push_back_slice(b"!");
+        assert_eq!(&buf[..], b"ip:udp:payload!");
+        assert_eq!(buf.into_vec(), b"ip:udp:payload!");
+    }
+
+    #[test]
+    fn headroom_reuse() {
+        let mut buf = HeadroomVec::with_capacity(8, 4);
+        assert_eq!(buf.headroom(), 8);
+
+        buf.push_back_slice(&[5, 6]);
+        buf.push_front_slice(&[3, 4]);
+        buf.push_front_slice(&[1, 2]);
+        assert_eq!(buf.headroom(), 4);
+        assert_eq!(&buf[..], &[1, 2, 3, 4, 5, 6]);
+
+        buf.set_growth(16, 0);
+        buf.push_front_slice(&[0; 5]);
+        assert!(buf.headroom() >= 16);
+        assert_eq!(&buf[..], &[0, 0, 0, 0, 0, 1, 2, 3, 4, 5, 6]);
+
+        buf.clear();
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn try_push_overflow() {
+        let mut buf = HeadroomVec::from(vec![0u32; 4]);
+        assert_eq!(
+            buf.try_push_front_slice(&[]).and_then(|_| buf.try_reserve_front(!0 - 2)),
+            Err(Error::CapacityOverflow { len: 4, additional: !0 - 2 })
+        );
+        assert_eq!(
+            buf.try_reserve_back(!0 >> 1),
+            Err(Error::CapacityOverflow { len: 4, additional: !0 >> 1 })
+        );
+        assert_eq!(&buf[..], &[0; 4]);
+    }
+
+    #[test]
+    fn zero_sized() {
+        let mut buf = HeadroomVec::new();
+        buf.push_front_slice(&[(); 3]);
+        buf.push_back_slice(&[(); 2]);
+        buf.push_front_slice(&[()]);
+        assert_eq!(buf.len(), 6);
+
+        assert_eq!(
+            buf.try_reserve_front(!0 - 5),
+            Err(Error::CapacityOverflow { len: 6, additional: !0 - 5 })
+        );
+        assert_eq!(
+            buf.try_reserve_back(!0 - 5),
+            Err(Error::CapacityOverflow { len: 6, additional: !0 - 5 })
+        );
+        assert_eq!(buf.try_reserve_back(!0 - 6), Ok(()));
+        assert_eq!(buf.clone().into_vec().len(), 6);
+    }
+
+    #[test]
+    fn from_vec() {
+        let mut buf = HeadroomVec::from(vec![3, 4]);
+        buf.push_front_slice(&[1, 2]);
+        buf[0] = 0;
+        assert_eq!(buf.clone().into_vec(), &[0, 2, 3, 4]);
+    }
+
+    /// Detect potential uninit values when running miri
+    #[test]
+    #[cfg(miri)]
+    fn push_front_bool() {
+        let mut buf = HeadroomVec::from(vec![false]);
+        buf.push_front_slice(&[true]);
+        buf.push_back_slice(&[true]);
+        buf.into_vec();
+    }
+}
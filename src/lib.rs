@@ -4,96 +4,1372 @@
 
 #[cfg(not(feature = "std"))]
 extern crate core as std;
-use std::ptr;
+#[cfg(feature = "alloc")]
+#[cfg_attr(test, macro_use)]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use std::{cmp, fmt, mem, ptr};
+use std::cmp::Ordering;
+use std::slice;
+use std::ops::{Bound, RangeBounds};
+#[cfg(feature = "alloc")]
+use std::mem::MaybeUninit;
+#[cfg(feature = "alloc")]
+use std::ops::Range;
+
+pub use cast::{as_bytes, as_bytes_mut, cast_slice, cast_slice_mut, read_at, try_cast_slice,
+               try_cast_slice_mut, try_read_at, try_write_at, write_at};
+pub use endian::{decode_from_bytes, encode_to_bytes, swap_bytes_in_place, to_be_in_place,
+                 to_endian_in_place, to_le_in_place, try_decode_from_bytes, try_encode_to_bytes,
+                 Endian, SwapBytes};
+#[cfg(feature = "alloc")]
+pub use gap::GapBuffer;
+#[cfg(feature = "alloc")]
+pub use headroom::HeadroomVec;
+pub use marker::{Pod, Zeroable};
+pub use ring::{RingBuffer, RingStorage};
+pub use secure::{secure_write_bytes, secure_zero, Zeroizing};
+#[cfg(feature = "alloc")]
+pub use uninit::UninitWriter;
 
 macro_rules! idx_check (
-    ($slice:expr, $idx:expr) => {
-        assert!($idx < $slice.len(),
-            concat!("`", stringify!($idx), "` ({}) out of bounds. Length: {}"),
-            $idx, $slice.len());
+    ($slice:expr, $idx:expr, $len:expr, $variant:ident) => {
+        if $idx >= $slice.len() {
+            return Err(Error::$variant { idx: $idx, len: $len, slice_len: $slice.len() });
+        }
     }
 );
 
 macro_rules! len_check (
-    ($slice:expr, $start:ident, $len:ident) => {
-        assert!(
-            $start.checked_add($len)
-                .expect(concat!("Overflow evaluating ", stringify!($start + $len)))
-                <= $slice.len(),
-            "Length {} starting at {} is out of bounds (slice len {}).", $len, $start, $slice.len()
-        )
+    ($slice:expr, $start:expr, $len:expr, $variant:ident) => {
+        match $start.checked_add($len) {
+            Some(end) if end <= $slice.len() => (),
+            Some(_) => return Err(Error::$variant { idx: $start, len: $len, slice_len: $slice.len() }),
+            None => return Err(Error::Overflow { start: $start, len: $len }),
+        }
     }
 );
 
+/// Invoke `$mac!` with `$args` followed by the array lengths the crate implements its traits for.
+///
+/// Const generics would cover every length, but require a newer compiler than the rest of the crate.
+macro_rules! for_array_lengths (
+    ($mac:ident!($($args:tt)*)) => {
+        $mac!($($args)* 0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20,
+              21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 48, 64, 96, 128, 256, 512, 1024,
+              2048, 4096);
+    }
+);
+
+/// Panic with the `Display` message of the error, if any.
+macro_rules! unwrap_check (
+    ($res:expr) => {
+        if let Err(e) = $res {
+            panic!("{}", e);
+        }
+    }
+);
+
+mod cast;
+#[cfg(feature = "alloc")]
+pub mod deque;
+mod endian;
+#[cfg(feature = "alloc")]
+mod gap;
+#[cfg(feature = "alloc")]
+mod headroom;
+mod marker;
+mod ring;
+mod secure;
+#[cfg(feature = "alloc")]
+mod uninit;
+
+/// Not public API; used by the code generated by `safemem-derive`.
+#[doc(hidden)]
+pub mod __private {
+    pub use std::mem::size_of;
+}
+
+/// Error returned by the `try_*` variants of this crate's functions.
+///
+/// The `Display` impl produces the same message the panicking variants panic with.
+///
+/// New variants may be added as the crate grows, so matches on it from other crates need a
+/// wildcard arm.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[allow(clippy::manual_non_exhaustive)]
+pub enum Error {
+    /// The source index, or the source index plus the length, was out of bounds.
+    SrcOutOfBounds {
+        /// The offending source index.
+        idx: usize,
+        /// The requested length.
+        len: usize,
+        /// The length of the slice being accessed.
+        slice_len: usize,
+    },
+    /// The destination index, or the destination index plus the length, was out of bounds.
+    DestOutOfBounds {
+        /// The offending destination index.
+        idx: usize,
+        /// The requested length.
+        len: usize,
+        /// The length of the slice being accessed.
+        slice_len: usize,
+    },
+    /// Evaluating `start + len` overflowed `usize`.
+    Overflow {
+        /// The start index of the range.
+        start: usize,
+        /// The requested length.
+        len: usize,
+    },
+    /// The start of a range was greater than its end.
+    BadRange {
+        /// The start of the range.
+        start: usize,
+        /// The end of the range.
+        end: usize,
+    },
+    /// Two ranges which were required to be disjoint and in order overlapped or were out of order.
+    Overlap {
+        /// The start of the first range.
+        first: usize,
+        /// The start of the second range.
+        second: usize,
+    },
+    /// The required capacity of a collection overflowed `usize` or exceeded `isize::MAX` bytes.
+    CapacityOverflow {
+        /// The length of the collection.
+        len: usize,
+        /// The number of additional elements requested.
+        additional: usize,
+    },
+    /// The allocator failed to provide the required capacity of a collection.
+    ///
+    /// Only returned with the `try_reserve` feature; otherwise allocation failure aborts as it
+    /// does for `Vec::reserve()`.
+    AllocFailed {
+        /// The length of the collection.
+        len: usize,
+        /// The number of additional elements requested.
+        additional: usize,
+    },
+    /// The size of a slice in bytes was not a multiple of the size of the type it was cast to.
+    SizeMismatch {
+        /// The size of the slice in bytes.
+        len: usize,
+        /// The size of the target type.
+        size: usize,
+    },
+    /// A slice was not sufficiently aligned for the type it was cast to.
+    Misaligned {
+        /// The address of the slice.
+        addr: usize,
+        /// The alignment of the target type.
+        align: usize,
+    },
+    /// Forces a wildcard arm in matches from other crates, like `#[non_exhaustive]` would on
+    /// newer compilers. Never constructed.
+    #[doc(hidden)]
+    __Nonexhaustive,
+}
+
+impl Error {
+    fn fmt_out_of_bounds(f: &mut fmt::Formatter, name: &str, idx: usize, len: usize, slice_len: usize)
+        -> fmt::Result {
+        if idx >= slice_len {
+            write!(f, "`{}` ({}) out of bounds. Length: {}", name, idx, slice_len)
+        } else {
+            write!(f, "Length {} starting at {} is out of bounds (slice len {}).", len, idx, slice_len)
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Error {
+    /// Replace the `len` and `additional` of a capacity error with those of the collection that
+    /// was being grown, e.g. if it reserved capacity in a new allocation.
+    fn for_collection(self, len: usize, additional: usize) -> Error {
+        match self {
+            Error::CapacityOverflow { .. } => Error::CapacityOverflow { len, additional },
+            Error::AllocFailed { .. } => Error::AllocFailed { len, additional },
+            e => e,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::SrcOutOfBounds { idx, len, slice_len } =>
+                Error::fmt_out_of_bounds(f, "src_idx", idx, len, slice_len),
+            Error::DestOutOfBounds { idx, len, slice_len } =>
+                Error::fmt_out_of_bounds(f, "dest_idx", idx, len, slice_len),
+            Error::Overflow { start, len } =>
+                write!(f, "Overflow evaluating {} + {}", start, len),
+            Error::BadRange { start, end } =>
+                write!(f, "Range start {} is greater than range end {}", start, end),
+            Error::Overlap { first, second } =>
+                write!(f, "Range starting at {} overlaps range starting at {}", second, first),
+            Error::CapacityOverflow { len, additional } =>
+                write!(f, "Capacity overflow reserving {} additional elements (len {})",
+                       additional, len),
+            Error::AllocFailed { len, additional } =>
+                write!(f, "Allocation failed reserving {} additional elements (len {})",
+                       additional, len),
+            Error::SizeMismatch { len, size } =>
+                write!(f, "Slice of {} bytes is not a multiple of element size {}", len, size),
+            Error::Misaligned { addr, align } =>
+                write!(f, "Address {:#x} is not aligned to {} bytes", addr, align),
+            Error::__Nonexhaustive => unreachable!(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
 /// Copy `len` elements from `src_idx` to `dest_idx`. Ranges may overlap.
 ///
 /// Safe wrapper for `memmove()`/`std::ptr::copy()`.
 ///
-/// ###Panics
-/// * If either `src_idx` or `dest_idx` are out of bounds, or if either of these plus `len` is out of
-/// bounds.
+/// ### Panics
+/// * If either `src_idx` or `dest_idx` are out of bounds, or if either of these plus `len` is out
+///   of bounds.
 /// * If `src_idx + len` or `dest_idx + len` overflows.
 pub fn copy_over<T: Copy>(slice: &mut [T], src_idx: usize, dest_idx: usize, len: usize) {
-    if slice.len() == 0 { return; }
+    unwrap_check!(try_copy_over(slice, src_idx, dest_idx, len));
+}
+
+/// Copy `len` elements from `src_idx` to `dest_idx`. Ranges may overlap.
+///
+/// Non-panicking version of [`copy_over()`](fn.copy_over.html).
+///
+/// ### Errors
+/// Returns an error under the same conditions that `copy_over()` panics. The slice is not
+/// modified in that case.
+#[allow(clippy::ptr_offset_with_cast)]
+pub fn try_copy_over<T: Copy>(slice: &mut [T], src_idx: usize, dest_idx: usize, len: usize)
+    -> Result<(), Error> {
+    if slice.is_empty() { return Ok(()); }
+
+    idx_check!(slice, src_idx, len, SrcOutOfBounds);
+    idx_check!(slice, dest_idx, len, DestOutOfBounds);
+    len_check!(slice, src_idx, len, SrcOutOfBounds);
+    len_check!(slice, dest_idx, len, DestOutOfBounds);
+
+    // At any point a Rust reference exists, the compiler is free to do this.
+    // So we explicitely add it to be caught by miri.
+    #[cfg(miri)]
+    slice.iter().copied().for_each(drop);
+
+    let ptr = slice.as_mut_ptr();
+
+    unsafe {
+        ptr::copy(ptr.offset(src_idx as isize), ptr.offset(dest_idx as isize), len);
+    }
+
+    Ok(())
+}
+
+/// Copy the elements in `src` to `dest_idx`. Ranges may overlap.
+///
+/// Equivalent to [`copy_over()`](fn.copy_over.html) but takes any kind of range for the
+/// source, e.g. `2..5`, `2..=4`, `2..` or `..`.
+///
+/// ### Panics
+/// * If `src` is out of bounds or its start is greater than its end.
+/// * If `dest_idx` is out of bounds, or if `dest_idx` plus the length of `src` is out of bounds.
+/// * If evaluating the bounds of `src` or `dest_idx` plus its length overflows.
+pub fn copy_within<T: Copy, R: RangeBounds<usize>>(slice: &mut [T], src: R, dest_idx: usize) {
+    unwrap_check!(try_copy_within(slice, src, dest_idx));
+}
+
+/// Copy the elements in `src` to `dest_idx`. Ranges may overlap.
+///
+/// Non-panicking version of [`copy_within()`](fn.copy_within.html).
+///
+/// ### Errors
+/// Returns an error under the same conditions that `copy_within()` panics. The slice is not
+/// modified in that case.
+pub fn try_copy_within<T: Copy, R: RangeBounds<usize>>(slice: &mut [T], src: R, dest_idx: usize)
+    -> Result<(), Error> {
+    let (src_idx, len) = range_to_start_len(&src, slice.len())?;
+    try_copy_over(slice, src_idx, dest_idx, len)
+}
+
+/// Convert `range` to a `(start, len)` pair, using `slice_len` as the end of unbounded ranges.
+///
+/// The result is not bounds-checked against `slice_len`.
+fn range_to_start_len<R: RangeBounds<usize>>(range: &R, slice_len: usize)
+    -> Result<(usize, usize), Error> {
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start.checked_add(1)
+            .ok_or(Error::Overflow { start, len: 1 })?,
+        Bound::Unbounded => 0,
+    };
+
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end.checked_add(1)
+            .ok_or(Error::Overflow { start: end, len: 1 })?,
+        Bound::Excluded(&end) => end,
+        Bound::Unbounded => slice_len,
+    };
+
+    if start > end {
+        return Err(Error::BadRange { start, end });
+    }
+
+    Ok((start, end - start))
+}
+
+/// Copy `len` elements from `src_idx` to `dest_idx` one after the other, front to back, as
+/// LZ77-style back-references do.
+///
+/// Unlike [`copy_over()`](fn.copy_over.html), if the destination overlaps the end of the
+/// source, the elements copied to the start of the destination are copied again: the first
+/// `dest_idx - src_idx` elements of the source are repeated to fill the destination. E.g. with a
+/// distance of 1 the destination is filled with the element at `src_idx`.
+///
+/// Each repetition of the pattern is copied with `memcpy()`/`std::ptr::copy_nonoverlapping()`,
+/// doubling the amount copied each time. With a distance of 1 and elements of one byte, the
+/// destination is filled with `memset()`/`std::ptr::write_bytes()` instead. If
+/// `dest_idx <= src_idx` this is the same as `copy_over()`.
+///
+/// ### Panics
+/// * If either `src_idx` or `dest_idx` are out of bounds, or if either of these plus `len` is out
+///   of bounds.
+/// * If `src_idx + len` or `dest_idx + len` overflows.
+pub fn copy_repeat<T: Copy>(slice: &mut [T], src_idx: usize, dest_idx: usize, len: usize) {
+    unwrap_check!(try_copy_repeat(slice, src_idx, dest_idx, len));
+}
+
+/// Copy `len` elements from `src_idx` to `dest_idx` one after the other, front to back, as
+/// LZ77-style back-references do.
+///
+/// Non-panicking version of [`copy_repeat()`](fn.copy_repeat.html).
+///
+/// ### Errors
+/// Returns an error under the same conditions that `copy_repeat()` panics. The slice is not
+/// modified in that case.
+pub fn try_copy_repeat<T: Copy>(slice: &mut [T], src_idx: usize, dest_idx: usize, len: usize)
+    -> Result<(), Error> {
+    if slice.is_empty() { return Ok(()); }
+
+    if dest_idx <= src_idx {
+        // Front-to-back copying gives the same result as `memmove()` here.
+        return try_copy_over(slice, src_idx, dest_idx, len);
+    }
+
+    idx_check!(slice, src_idx, len, SrcOutOfBounds);
+    idx_check!(slice, dest_idx, len, DestOutOfBounds);
+    len_check!(slice, src_idx, len, SrcOutOfBounds);
+    len_check!(slice, dest_idx, len, DestOutOfBounds);
+
+    unsafe {
+        copy_repeat_raw(slice.as_mut_ptr(), src_idx, dest_idx, len);
+    }
+
+    Ok(())
+}
+
+/// Copy `len` elements from `src_idx` to `dest_idx` front to back.
+///
+/// ### Safety
+/// `src_idx < dest_idx` and `ptr.add(dest_idx + len)` must be in bounds of the same allocation.
+/// Only the elements from `src_idx` to `dest_idx` need to be initialized.
+unsafe fn copy_repeat_raw<T: Copy>(ptr: *mut T, src_idx: usize, dest_idx: usize, len: usize) {
+    let distance = dest_idx - src_idx;
+
+    if distance == 1 && mem::size_of::<T>() == 1 {
+        // The byte is a copy of a valid `T`, and a one-byte type has no padding.
+        let byte = *(ptr.add(src_idx) as *const u8);
+        ptr::write_bytes(ptr.add(dest_idx) as *mut u8, byte, len);
+        return;
+    }
+
+    let mut copied = 0;
+
+    while copied < len {
+        // `src_idx .. dest_idx + copied` repeats with a period of `distance` and `copied` is a
+        // multiple of `distance`, so it can be copied to `dest_idx + copied` as-is.
+        let copy_len = cmp::min(distance + copied, len - copied);
+        ptr::copy_nonoverlapping(ptr.add(src_idx), ptr.add(dest_idx + copied), copy_len);
+        copied += copy_len;
+    }
+}
+
+/// Swap the `len` elements starting at `a_idx` with the `len` elements starting at `b_idx`.
+///
+/// Safe wrapper for `std::ptr::swap_nonoverlapping()`.
+///
+/// ### Panics
+/// * If either `a_idx` or `b_idx` are out of bounds, or if either of these plus `len` is out of
+///   bounds.
+/// * If `a_idx + len` or `b_idx + len` overflows.
+/// * If the two ranges overlap.
+pub fn swap_ranges<T: Copy>(slice: &mut [T], a_idx: usize, b_idx: usize, len: usize) {
+    unwrap_check!(try_swap_ranges(slice, a_idx, b_idx, len));
+}
+
+/// Swap the `len` elements starting at `a_idx` with the `len` elements starting at `b_idx`.
+///
+/// Non-panicking version of [`swap_ranges()`](fn.swap_ranges.html).
+///
+/// ### Errors
+/// Returns an error under the same conditions that `swap_ranges()` panics. Bounds errors for
+/// `a_idx` are reported as `Error::SrcOutOfBounds` and those for `b_idx` as
+/// `Error::DestOutOfBounds`. The slice is not modified in that case.
+pub fn try_swap_ranges<T: Copy>(slice: &mut [T], a_idx: usize, b_idx: usize, len: usize)
+    -> Result<(), Error> {
+    if slice.is_empty() { return Ok(()); }
+
+    idx_check!(slice, a_idx, len, SrcOutOfBounds);
+    idx_check!(slice, b_idx, len, DestOutOfBounds);
+    len_check!(slice, a_idx, len, SrcOutOfBounds);
+    len_check!(slice, b_idx, len, DestOutOfBounds);
+
+    if len > 0 && a_idx < b_idx + len && b_idx < a_idx + len {
+        return Err(Error::Overlap { first: a_idx, second: b_idx });
+    }
+
+    let ptr = slice.as_mut_ptr();
+
+    unsafe {
+        ptr::swap_nonoverlapping(ptr.add(a_idx), ptr.add(b_idx), len);
+    }
+
+    Ok(())
+}
+
+/// Rotate the elements in `range` so that the element at `mid` becomes the first element of
+/// `range`, i.e. swap the adjacent blocks `range.start .. mid` and `mid .. range.end`.
+///
+/// Equivalent to `slice[range].rotate_left(mid - range.start)`, which does not allocate.
+///
+/// ### Panics
+/// * If `range` is out of bounds or its start is greater than its end.
+/// * If `mid` is not within `range`.
+/// * If evaluating the bounds of `range` overflows.
+pub fn rotate_range<T: Copy, R: RangeBounds<usize>>(slice: &mut [T], range: R, mid: usize) {
+    unwrap_check!(try_rotate_range(slice, range, mid));
+}
+
+/// Rotate the elements in `range` so that the element at `mid` becomes the first element of
+/// `range`.
+///
+/// Non-panicking version of [`rotate_range()`](fn.rotate_range.html).
+///
+/// ### Errors
+/// Returns an error under the same conditions that `rotate_range()` panics. If `mid` is not
+/// within `range`, `Error::BadRange` is returned for whichever of `start .. mid` and
+/// `mid .. end` is reversed. The slice is not modified in that case.
+pub fn try_rotate_range<T: Copy, R: RangeBounds<usize>>(slice: &mut [T], range: R, mid: usize)
+    -> Result<(), Error> {
+    let (start, len) = range_to_start_len(&range, slice.len())?;
+    len_check!(slice, start, len, DestOutOfBounds);
+
+    let end = start + len;
+    if mid < start {
+        return Err(Error::BadRange { start, end: mid });
+    }
+    if mid > end {
+        return Err(Error::BadRange { start: mid, end });
+    }
+
+    slice[start .. end].rotate_left(mid - start);
+
+    Ok(())
+}
+
+/// Move the elements in `src` so that they start at `dest_idx`, shifting the elements between
+/// their old and new positions to fill the space they left.
+///
+/// `dest_idx` is the position of the block *after* the move, so the block and the elements
+/// around it still fit in the slice. Equivalent to a [`rotate_range()`](fn.rotate_range.html)
+/// of the span covering both positions.
+///
+/// ### Panics
+/// * If `src` is out of bounds or its start is greater than its end.
+/// * If `dest_idx` plus the length of `src` is out of bounds.
+/// * If evaluating the bounds of `src` or `dest_idx` plus its length overflows.
+pub fn move_range<T: Copy, R: RangeBounds<usize>>(slice: &mut [T], src: R, dest_idx: usize) {
+    unwrap_check!(try_move_range(slice, src, dest_idx));
+}
+
+/// Move the elements in `src` so that they start at `dest_idx`, shifting the elements between
+/// their old and new positions to fill the space they left.
+///
+/// Non-panicking version of [`move_range()`](fn.move_range.html).
+///
+/// ### Errors
+/// Returns an error under the same conditions that `move_range()` panics. The slice is not
+/// modified in that case.
+pub fn try_move_range<T: Copy, R: RangeBounds<usize>>(slice: &mut [T], src: R, dest_idx: usize)
+    -> Result<(), Error> {
+    let (src_idx, len) = range_to_start_len(&src, slice.len())?;
+    len_check!(slice, src_idx, len, SrcOutOfBounds);
+    len_check!(slice, dest_idx, len, DestOutOfBounds);
+
+    if dest_idx < src_idx {
+        // [. D . . S S .] -> [. S S D . . .]
+        slice[dest_idx .. src_idx + len].rotate_left(src_idx - dest_idx);
+    } else if dest_idx > src_idx {
+        // [. S S . . D .] -> [. . . . S S .]
+        slice[src_idx .. dest_idx + len].rotate_left(len);
+    }
+
+    Ok(())
+}
+
+/// Safe wrapper for `std::ptr::write_bytes()`/`memset()`.
+///
+/// Use [`as_bytes_mut()`](fn.as_bytes_mut.html) to apply it to slices of other `Pod` types.
+pub fn write_bytes(slice: &mut [u8], byte: u8) {
+    unsafe {
+        ptr::write_bytes(slice.as_mut_ptr(), byte, slice.len());
+    }
+}
+
+/// Compare `a` and `b` lexicographically, like `memcmp()` but also comparing the lengths if one
+/// is a prefix of the other.
+///
+/// Equivalent to `a.cmp(b)`, using [`mismatch()`](fn.mismatch.html) to find the first
+/// differing byte.
+pub fn compare(a: &[u8], b: &[u8]) -> Ordering {
+    match mismatch(a, b) {
+        Some(idx) if idx < a.len() && idx < b.len() => a[idx].cmp(&b[idx]),
+        _ => a.len().cmp(&b.len()),
+    }
+}
+
+/// Return the index of the first byte at which `a` and `b` differ, or `None` if they are equal.
+///
+/// If one is a prefix of the other, the length of the shorter one is returned.
+///
+/// The bytes are compared a `usize` at a time, only falling back to single bytes for the
+/// remainder.
+pub fn mismatch(a: &[u8], b: &[u8]) -> Option<usize> {
+    const WORD: usize = mem::size_of::<usize>();
+
+    let len = cmp::min(a.len(), b.len());
+    let mut idx = 0;
+
+    while len - idx >= WORD {
+        // Read the words as little endian so the first byte in memory is the least significant.
+        let (x, y) = unsafe {
+            (
+                usize::from_le(ptr::read_unaligned(a.as_ptr().add(idx) as *const usize)),
+                usize::from_le(ptr::read_unaligned(b.as_ptr().add(idx) as *const usize)),
+            )
+        };
+
+        if x != y {
+            return Some(idx + (x ^ y).trailing_zeros() as usize / 8);
+        }
+
+        idx += WORD;
+    }
+
+    while idx < len {
+        if a[idx] != b[idx] { return Some(idx); }
+        idx += 1;
+    }
+
+    if a.len() != b.len() { Some(len) } else { None }
+}
+
+/// Compare the `len` bytes starting at `a_idx` with the `len` bytes starting at `b_idx`
+/// lexicographically. Ranges may overlap.
+///
+/// Within-slice version of [`compare()`](fn.compare.html).
+///
+/// ### Panics
+/// * If either `a_idx` or `b_idx` are out of bounds, or if either of these plus `len` is out of
+///   bounds.
+/// * If `a_idx + len` or `b_idx + len` overflows.
+pub fn compare_ranges(slice: &[u8], a_idx: usize, b_idx: usize, len: usize) -> Ordering {
+    match try_compare_ranges(slice, a_idx, b_idx, len) {
+        Ok(ord) => ord,
+        Err(e) => panic!("{}", e),
+    }
+}
+
+/// Compare the `len` bytes starting at `a_idx` with the `len` bytes starting at `b_idx`
+/// lexicographically. Ranges may overlap.
+///
+/// Non-panicking version of [`compare_ranges()`](fn.compare_ranges.html).
+///
+/// ### Errors
+/// Returns an error under the same conditions that `compare_ranges()` panics. Bounds errors for
+/// `a_idx` are reported as `Error::SrcOutOfBounds` and those for `b_idx` as
+/// `Error::DestOutOfBounds`.
+pub fn try_compare_ranges(slice: &[u8], a_idx: usize, b_idx: usize, len: usize)
+    -> Result<Ordering, Error> {
+    if slice.is_empty() { return Ok(Ordering::Equal); }
+
+    idx_check!(slice, a_idx, len, SrcOutOfBounds);
+    idx_check!(slice, b_idx, len, DestOutOfBounds);
+    len_check!(slice, a_idx, len, SrcOutOfBounds);
+    len_check!(slice, b_idx, len, DestOutOfBounds);
+
+    Ok(compare(&slice[a_idx .. a_idx + len], &slice[b_idx .. b_idx + len]))
+}
+
+/// Fill `slice` with copies of `value`.
+///
+/// Generalization of [`write_bytes()`](fn.write_bytes.html) to any `T: Copy`. `value` is written
+/// to the first element, after which the filled prefix is repeatedly doubled with
+/// `memcpy()`/`std::ptr::copy_nonoverlapping()`, so only `O(log(slice.len()))` copies are made.
+///
+/// The bytes of `value` are never inspected to select a `memset()` instead, as `T` may contain
+/// padding or other uninitialized bytes, which may not be read. Use
+/// [`fill_pod()`](fn.fill_pod.html) for types without padding.
+pub fn fill<T: Copy>(slice: &mut [T], value: T) {
+    let len = slice.len();
+    if len == 0 { return; }
+
+    slice[0] = value;
+    double_prefix(slice, 1);
+}
+
+/// Fill `slice` with copies of `value`, using `memset()`/`std::ptr::write_bytes()` if all bytes
+/// of `value` are identical, e.g. when filling with zeroes or all ones.
+///
+/// Otherwise equivalent to [`fill()`](fn.fill.html). Unlike there, the bytes of `value` may be
+/// inspected as `T: Pod` has no padding.
+pub fn fill_pod<T: Pod>(slice: &mut [T], value: T) {
+    if let Some((&byte, rest)) = as_bytes(slice::from_ref(&value)).split_first() {
+        if rest.iter().all(|&b| b == byte) {
+            return write_bytes(as_bytes_mut(slice), byte);
+        }
+    }
+
+    fill(slice, value);
+}
+
+/// Fill `dest` with repetitions of `pattern`, truncating the last repetition if `dest.len()` is not
+/// a multiple of `pattern.len()`.
+///
+/// The first repetition is copied in, after which the filled prefix is repeatedly doubled with
+/// `memcpy()`/`std::ptr::copy_nonoverlapping()`, so only `O(log(dest.len() / pattern.len()))`
+/// copies are made.
+///
+/// ### Panics
+/// If `pattern` is empty and `dest` is not.
+pub fn fill_pattern<T: Copy>(dest: &mut [T], pattern: &[T]) {
+    if dest.is_empty() { return; }
+
+    assert!(!pattern.is_empty(), "Cannot fill a slice of len {} with an empty pattern", dest.len());
+
+    let first_len = cmp::min(pattern.len(), dest.len());
+    dest[..first_len].copy_from_slice(&pattern[..first_len]);
+    double_prefix(dest, first_len);
+}
+
+/// Fill the rest of `slice` by repeatedly doubling the first `filled` elements.
+fn double_prefix<T: Copy>(slice: &mut [T], mut filled: usize) {
+    let len = slice.len();
+
+    while filled < len {
+        let copy_len = cmp::min(filled, len - filled);
+        let (head, tail) = slice.split_at_mut(filled);
+        tail[..copy_len].copy_from_slice(&head[..copy_len]);
+        filled += copy_len;
+    }
+}
+
+/// Fill the elements in `range` with copies of `value`.
+///
+/// Equivalent to [`fill()`](fn.fill.html) on the subslice.
+///
+/// ### Panics
+/// * If `range` is out of bounds or its start is greater than its end.
+/// * If evaluating the bounds of `range` overflows.
+pub fn fill_range<T: Copy, R: RangeBounds<usize>>(slice: &mut [T], range: R, value: T) {
+    unwrap_check!(try_fill_range(slice, range, value));
+}
+
+/// Fill the elements in `range` with copies of `value`.
+///
+/// Non-panicking version of [`fill_range()`](fn.fill_range.html).
+///
+/// ### Errors
+/// Returns an error under the same conditions that `fill_range()` panics. The slice is not
+/// modified in that case.
+pub fn try_fill_range<T: Copy, R: RangeBounds<usize>>(slice: &mut [T], range: R, value: T)
+    -> Result<(), Error> {
+    let (start, len) = range_to_start_len(&range, slice.len())?;
+    len_check!(slice, start, len, DestOutOfBounds);
+
+    fill(&mut slice[start .. start + len], value);
+
+    Ok(())
+}
+
+/// Prepend `elems` to `vec`, resizing if necessary.
+///
+/// Equivalent to [`insert_slice(vec, 0, elems)`](fn.insert_slice.html).
+///
+/// ### Panics
+///
+/// If the new capacity overflows.
+#[cfg(feature = "alloc")]
+pub fn prepend<T: Copy>(elems: &[T], vec: &mut Vec<T>) {
+    insert_slice(vec, 0, elems);
+}
+
+/// Prepend `elems` to `vec`, resizing if necessary.
+///
+/// Non-panicking version of [`prepend()`](fn.prepend.html).
+///
+/// ### Errors
+/// If the new capacity overflows or allocating it fails. The `Vec` is not modified in that case.
+///
+/// Without the `try_reserve` feature, allocation failure aborts the process instead.
+#[cfg(feature = "alloc")]
+pub fn try_prepend<T: Copy>(elems: &[T], vec: &mut Vec<T>) -> Result<(), Error> {
+    try_insert_slice(vec, 0, elems)
+}
+
+/// Prepend up to `n` elements to `vec` by writing them directly into its front with `f`,
+/// returning the number of elements written.
+///
+/// The existing elements are moved out of the way once, after which `f` is called with an
+/// [`UninitWriter`](struct.UninitWriter.html) over the `n` element gap at the front. Only the
+/// elements `f` writes become part of `vec`; if it writes fewer than `n`, the existing elements
+/// are moved back to close the rest of the gap. This avoids encoding into a temporary buffer
+/// first, as [`prepend()`](fn.prepend.html) would require.
+///
+/// If `f` panics, `vec` is restored to its previous contents before unwinding.
+///
+/// ### Panics
+/// * If the new capacity overflows.
+/// * If `f` panics.
+#[cfg(feature = "alloc")]
+pub fn prepend_with<T: Copy, F>(vec: &mut Vec<T>, n: usize, f: F) -> usize
+where F: FnOnce(&mut UninitWriter<T>) {
+    match prepend_with_impl(vec, n, f, Infallible) {
+        Ok(written) => written,
+        Err(e) => panic!("{}", e),
+    }
+}
+
+/// Prepend up to `n` elements to `vec` by writing them directly into its front with `f`,
+/// returning the number of elements written.
+///
+/// Non-panicking version of [`prepend_with()`](fn.prepend_with.html) with regards to
+/// allocation.
+///
+/// ### Errors
+/// If the new capacity overflows or allocating it fails. The `Vec` is not modified and `f` is
+/// not called in that case.
+///
+/// Without the `try_reserve` feature, allocation failure aborts the process instead.
+///
+/// ### Panics
+/// If `f` panics.
+#[cfg(feature = "alloc")]
+pub fn try_prepend_with<T: Copy, F>(vec: &mut Vec<T>, n: usize, f: F) -> Result<usize, Error>
+where F: FnOnce(&mut UninitWriter<T>) {
+    prepend_with_impl(vec, n, f, Fallible)
+}
+
+#[cfg(feature = "alloc")]
+fn prepend_with_impl<T: Copy, F>(vec: &mut Vec<T>, n: usize, f: F, fallibility: Fallibility)
+    -> Result<usize, Error>
+where F: FnOnce(&mut UninitWriter<T>) {
+    /// Closes the unwritten part of the gap when dropped, including when unwinding.
+    struct CloseGap<'a, T: 'a> {
+        vec: &'a mut Vec<T>,
+        old_len: usize,
+        gap: usize,
+        written: usize,
+    }
+
+    impl<'a, T> Drop for CloseGap<'a, T> {
+        fn drop(&mut self) {
+            unsafe {
+                let ptr = self.vec.as_mut_ptr();
+                ptr::copy(ptr.add(self.gap), ptr.add(self.written), self.old_len);
+                // Set the len *after* having initialized the elements.
+                self.vec.set_len(self.written + self.old_len);
+            }
+        }
+    }
+
+    reserve(vec, n, fallibility)?;
+
+    let old_len = vec.len();
+    let ptr = vec.as_mut_ptr();
+
+    unsafe {
+        // Move the old elements down to the end.
+        ptr::copy(ptr, ptr.add(n), old_len);
+        // The gap is uninitialized as far as `vec` is concerned until `CloseGap` runs.
+        vec.set_len(0);
+    }
+
+    let mut close_gap = CloseGap { vec, old_len, gap: n, written: 0 };
+
+    let gap = unsafe { slice::from_raw_parts_mut(ptr as *mut MaybeUninit<T>, n) };
+    let mut writer = UninitWriter::new(gap);
+    f(&mut writer);
+
+    close_gap.written = writer.len();
+    Ok(close_gap.written)
+}
+
+/// Insert `elems` into `vec` at `dest_idx`, shifting the elements after it towards the end and
+/// resizing if necessary.
+///
+/// ### Panics
+/// * If `dest_idx > vec.len()`.
+/// * If the new capacity overflows.
+#[cfg(feature = "alloc")]
+pub fn insert_slice<T: Copy>(vec: &mut Vec<T>, dest_idx: usize, elems: &[T]) {
+    unwrap_check!(insert_slice_impl(vec, dest_idx, elems, Infallible));
+}
+
+/// Insert `elems` into `vec` at `dest_idx`, shifting the elements after it towards the end and
+/// resizing if necessary.
+///
+/// Non-panicking version of [`insert_slice()`](fn.insert_slice.html).
+///
+/// ### Errors
+/// Returns an error under the same conditions that `insert_slice()` panics, or if allocating the
+/// new capacity fails. The `Vec` is not modified in that case.
+///
+/// Without the `try_reserve` feature, allocation failure aborts the process instead.
+#[cfg(feature = "alloc")]
+pub fn try_insert_slice<T: Copy>(vec: &mut Vec<T>, dest_idx: usize, elems: &[T])
+    -> Result<(), Error> {
+    insert_slice_impl(vec, dest_idx, elems, Fallible)
+}
+
+#[cfg(feature = "alloc")]
+fn insert_slice_impl<T: Copy>(vec: &mut Vec<T>, dest_idx: usize, elems: &[T],
+                              fallibility: Fallibility) -> Result<(), Error> {
+    let old_len = vec.len(); // `<= isize::MAX as usize`
+    let elems_len = elems.len(); // `<= isize::MAX as usize`
+
+    shift_tail(vec, dest_idx, elems_len, fallibility)?;
+
+    unsafe {
+        // Copy the input elements into the gap.
+        ptr::copy_nonoverlapping(
+            elems.as_ptr(),
+            vec.as_mut_ptr().add(dest_idx),
+            elems_len,
+        );
+        // Set the len *after* having initialized the elements.
+        vec.set_len(old_len + elems_len);
+    }
+
+    Ok(())
+}
+
+/// Open a gap of `n` elements in `vec` at `dest_idx`, shifting the elements after it towards the
+/// end and resizing if necessary, and return it filled with `fill_value` for the caller to
+/// overwrite.
+///
+/// Generalization of [`insert_slice()`](fn.insert_slice.html) for when the elements to insert
+/// are not already available as a slice. See [`close_gap()`](fn.close_gap.html) for the
+/// inverse.
+///
+/// ### Panics
+/// * If `dest_idx > vec.len()`.
+/// * If the new capacity overflows.
+#[cfg(feature = "alloc")]
+pub fn open_gap<T: Copy>(vec: &mut Vec<T>, dest_idx: usize, n: usize, fill_value: T) -> &mut [T] {
+    match open_gap_impl(vec, dest_idx, n, fill_value, Infallible) {
+        Ok(gap) => gap,
+        Err(e) => panic!("{}", e),
+    }
+}
+
+/// Open a gap of `n` elements in `vec` at `dest_idx`, shifting the elements after it towards the
+/// end and resizing if necessary, and return it filled with `fill_value` for the caller to
+/// overwrite.
+///
+/// Non-panicking version of [`open_gap()`](fn.open_gap.html).
+///
+/// ### Errors
+/// Returns an error under the same conditions that `open_gap()` panics, or if allocating the
+/// new capacity fails. The `Vec` is not modified in that case.
+///
+/// Without the `try_reserve` feature, allocation failure aborts the process instead.
+#[cfg(feature = "alloc")]
+pub fn try_open_gap<T: Copy>(vec: &mut Vec<T>, dest_idx: usize, n: usize, fill_value: T)
+    -> Result<&mut [T], Error> {
+    open_gap_impl(vec, dest_idx, n, fill_value, Fallible)
+}
+
+#[cfg(feature = "alloc")]
+fn open_gap_impl<T: Copy>(vec: &mut Vec<T>, dest_idx: usize, n: usize, fill_value: T,
+                          fallibility: Fallibility) -> Result<&mut [T], Error> {
+    let old_len = vec.len();
+
+    shift_tail(vec, dest_idx, n, fallibility)?;
+
+    if n > 0 {
+        unsafe {
+            let ptr = vec.as_mut_ptr();
+            // Fill the gap by writing the first element and repeating it.
+            ptr::write(ptr.add(dest_idx), fill_value);
+            copy_repeat_raw(ptr, dest_idx, dest_idx + 1, n - 1);
+            // Set the len *after* having initialized the elements.
+            vec.set_len(old_len + n);
+        }
+    }
+
+    Ok(&mut vec[dest_idx .. dest_idx + n])
+}
+
+/// Close a gap of `n` elements in `vec` at `src_idx`, shifting the elements after it towards the
+/// start.
+///
+/// Inverse of [`open_gap()`](fn.open_gap.html); equivalent to
+/// [`remove_range(vec, src_idx .. src_idx + n)`](fn.remove_range.html).
+///
+/// ### Panics
+/// * If `src_idx + n` is out of bounds.
+/// * If `src_idx + n` overflows.
+#[cfg(feature = "alloc")]
+pub fn close_gap<T: Copy>(vec: &mut Vec<T>, src_idx: usize, n: usize) {
+    unwrap_check!(try_close_gap(vec, src_idx, n));
+}
+
+/// Close a gap of `n` elements in `vec` at `src_idx`, shifting the elements after it towards the
+/// start.
+///
+/// Non-panicking version of [`close_gap()`](fn.close_gap.html).
+///
+/// ### Errors
+/// Returns an error under the same conditions that `close_gap()` panics. The `Vec` is not
+/// modified in that case.
+#[cfg(feature = "alloc")]
+pub fn try_close_gap<T: Copy>(vec: &mut Vec<T>, src_idx: usize, n: usize) -> Result<(), Error> {
+    len_check!(vec, src_idx, n, SrcOutOfBounds);
+    try_remove_range(vec, src_idx .. src_idx + n)
+}
+
+/// Reserve room for `n` more elements in `vec` and move the elements from `dest_idx` on `n`
+/// elements towards the end, leaving the length unchanged.
+///
+/// The caller must initialize the gap and then set the length to `vec.len() + n`.
+#[cfg(feature = "alloc")]
+fn shift_tail<T: Copy>(vec: &mut Vec<T>, dest_idx: usize, n: usize, fallibility: Fallibility)
+    -> Result<(), Error> {
+    let old_len = vec.len(); // `<= isize::MAX as usize`
+    if dest_idx > old_len {
+        return Err(Error::DestOutOfBounds { idx: dest_idx, len: n, slice_len: old_len });
+    }
+
+    reserve(vec, n, fallibility)?;
+
+    unsafe {
+        let ptr = vec.as_mut_ptr().add(dest_idx);
+        // Move the elements after `dest_idx` down to the end.
+        ptr::copy(
+            ptr,
+            ptr.add(n),
+            old_len - dest_idx,
+        );
+    }
+
+    Ok(())
+}
+
+/// Remove the elements in `range` from `vec`, shifting the elements after it towards the start.
+///
+/// Like `Vec::drain()` but without the iterator.
+///
+/// ### Panics
+/// * If `range` is out of bounds or its start is greater than its end.
+/// * If evaluating the bounds of `range` overflows.
+#[cfg(feature = "alloc")]
+pub fn remove_range<T: Copy, R: RangeBounds<usize>>(vec: &mut Vec<T>, range: R) {
+    unwrap_check!(try_remove_range(vec, range));
+}
+
+/// Remove the elements in `range` from `vec`, shifting the elements after it towards the start.
+///
+/// Non-panicking version of [`remove_range()`](fn.remove_range.html).
+///
+/// ### Errors
+/// Returns an error under the same conditions that `remove_range()` panics. The `Vec` is not
+/// modified in that case.
+#[cfg(feature = "alloc")]
+pub fn try_remove_range<T: Copy, R: RangeBounds<usize>>(vec: &mut Vec<T>, range: R)
+    -> Result<(), Error> {
+    let (start, len) = range_to_start_len(&range, vec.len())?;
+    len_check!(vec, start, len, SrcOutOfBounds);
+
+    if len == 0 { return Ok(()); }
+
+    let old_len = vec.len();
+    unsafe {
+        let ptr = vec.as_mut_ptr().add(start);
+        // Move the elements after the range up to its start.
+        ptr::copy(
+            ptr.add(len),
+            ptr,
+            old_len - start - len,
+        );
+        vec.set_len(old_len - len);
+    }
+
+    Ok(())
+}
+
+/// Remove the first `n` elements of `vec`, shifting the rest towards the start.
+///
+/// Equivalent to [`remove_range(vec, ..n)`](fn.remove_range.html).
+///
+/// ### Panics
+/// If `n > vec.len()`.
+#[cfg(feature = "alloc")]
+pub fn truncate_front<T: Copy>(vec: &mut Vec<T>, n: usize) {
+    remove_range(vec, ..n);
+}
+
+/// Remove the first `n` elements of `vec`, shifting the rest towards the start.
+///
+/// Non-panicking version of [`truncate_front()`](fn.truncate_front.html).
+///
+/// ### Errors
+/// If `n > vec.len()`. The `Vec` is not modified in that case.
+#[cfg(feature = "alloc")]
+pub fn try_truncate_front<T: Copy>(vec: &mut Vec<T>, n: usize) -> Result<(), Error> {
+    try_remove_range(vec, ..n)
+}
+
+/// Replace the elements in `range` with `replacement`, which may be of a different length,
+/// shifting the elements after `range` as needed and resizing if necessary.
+///
+/// Like `Vec::splice()` but without the iterators.
+///
+/// ### Panics
+/// * If `range` is out of bounds or its start is greater than its end.
+/// * If evaluating the bounds of `range` overflows.
+/// * If the new capacity overflows.
+#[cfg(feature = "alloc")]
+pub fn replace_range<T: Copy, R: RangeBounds<usize>>(
+    vec: &mut Vec<T>, range: R, replacement: &[T]
+) {
+    unwrap_check!(replace_range_impl(vec, range, replacement, Infallible));
+}
+
+/// Replace the elements in `range` with `replacement`, which may be of a different length,
+/// shifting the elements after `range` as needed and resizing if necessary.
+///
+/// Non-panicking version of [`replace_range()`](fn.replace_range.html).
+///
+/// ### Errors
+/// Returns an error under the same conditions that `replace_range()` panics, or if allocating the
+/// new capacity fails. The `Vec` is not modified in that case.
+///
+/// Without the `try_reserve` feature, allocation failure aborts the process instead.
+#[cfg(feature = "alloc")]
+pub fn try_replace_range<T: Copy, R: RangeBounds<usize>>(
+    vec: &mut Vec<T>, range: R, replacement: &[T]
+) -> Result<(), Error> {
+    replace_range_impl(vec, range, replacement, Fallible)
+}
+
+#[cfg(feature = "alloc")]
+fn replace_range_impl<T: Copy, R: RangeBounds<usize>>(
+    vec: &mut Vec<T>, range: R, replacement: &[T], fallibility: Fallibility
+) -> Result<(), Error> {
+    let (start, len) = range_to_start_len(&range, vec.len())?;
+    len_check!(vec, start, len, DestOutOfBounds);
+
+    let old_len = vec.len(); // `<= isize::MAX as usize`
+    let new_len = replacement.len(); // `<= isize::MAX as usize`
+
+    if new_len > len {
+        reserve(vec, new_len - len, fallibility)?;
+    }
+
+    unsafe {
+        let ptr = vec.as_mut_ptr().add(start);
+        // Move the elements after the range to directly after the replacement.
+        if new_len != len {
+            ptr::copy(
+                ptr.add(len),
+                ptr.add(new_len),
+                old_len - start - len,
+            );
+        }
+        // Copy the replacement elements into the range.
+        ptr::copy_nonoverlapping(
+            replacement.as_ptr(),
+            ptr,
+            new_len,
+        );
+        // Set the len *after* having initialized the elements.
+        vec.set_len(old_len - len + new_len);
+    }
+
+    Ok(())
+}
+
+/// Apply a batch of edits to `vec` in a single pass.
+///
+/// Each edit is a `(range, replacement)` pair, replacing the elements in `range` with
+/// `replacement` as [`replace_range()`](fn.replace_range.html) would. An empty range is an
+/// insertion; an empty replacement is a removal. All ranges refer to indices in `vec` *before*
+/// any edits are applied. The edits must be sorted by position and their ranges may not overlap,
+/// though they may touch.
+///
+/// The new length is calculated once and every element that is not part of an edit is moved at
+/// most once, so this is `O(vec.len() + total replacement length)` regardless of the number of
+/// edits.
+///
+/// ### Panics
+/// * If any range is out of bounds or its start is greater than its end.
+/// * If any two ranges overlap or are out of order.
+/// * If the new capacity overflows.
+#[cfg(feature = "alloc")]
+pub fn apply_edits<T: Copy>(vec: &mut Vec<T>, edits: &[(Range<usize>, &[T])]) {
+    unwrap_check!(apply_edits_impl(vec, edits, Infallible));
+}
+
+/// Apply a batch of edits to `vec` in a single pass.
+///
+/// Non-panicking version of [`apply_edits()`](fn.apply_edits.html).
+///
+/// ### Errors
+/// Returns an error under the same conditions that `apply_edits()` panics, or if allocating the
+/// new capacity fails. The `Vec` is not modified in that case.
+///
+/// Without the `try_reserve` feature, allocation failure aborts the process instead.
+#[cfg(feature = "alloc")]
+pub fn try_apply_edits<T: Copy>(vec: &mut Vec<T>, edits: &[(Range<usize>, &[T])])
+    -> Result<(), Error> {
+    apply_edits_impl(vec, edits, Fallible)
+}
+
+#[cfg(feature = "alloc")]
+fn apply_edits_impl<T: Copy>(vec: &mut Vec<T>, edits: &[(Range<usize>, &[T])],
+                             fallibility: Fallibility) -> Result<(), Error> {
+    let old_len = vec.len(); // `<= isize::MAX as usize`
+
+    let mut removed = 0;
+    let mut inserted = 0usize;
+    let mut prev: Option<&Range<usize>> = None;
+
+    for &(ref range, replacement) in edits {
+        if range.start > range.end {
+            return Err(Error::BadRange { start: range.start, end: range.end });
+        }
+        let len = range.end - range.start;
+        len_check!(vec, range.start, len, DestOutOfBounds);
+
+        if let Some(prev) = prev {
+            if prev.end > range.start {
+                return Err(Error::Overlap { first: prev.start, second: range.start });
+            }
+        }
+        prev = Some(range);
+
+        removed += len;
+        // The number of additional elements saturates at `usize::MAX`.
+        inserted = inserted.checked_add(replacement.len())
+            .ok_or(Error::CapacityOverflow { len: old_len, additional: !0 })?;
+    }
+
+    if edits.is_empty() { return Ok(()); }
+
+    // `removed <= old_len` as the ranges are disjoint and in bounds.
+    let new_len = (old_len - removed).checked_add(inserted)
+        .ok_or_else(|| Error::CapacityOverflow { len: old_len, additional: inserted - removed })?;
+
+    if new_len > old_len {
+        reserve(vec, new_len - old_len, fallibility)?;
+    }
+
+    // The kept segment following edit `k` is `seg_start(k) .. seg_end(k)` before the edits.
+    let seg_start = |k: usize| edits[k].0.end;
+    let seg_end = |k: usize| edits.get(k + 1).map_or(old_len, |edit| edit.0.start);
+    let seg_len = |k: usize| seg_end(k) - seg_start(k);
+
+    let ptr = vec.as_mut_ptr();
+
+    // Move the kept segments to their final positions. A segment moving towards the end may
+    // overwrite the following segments if they also move towards the end, so runs of such
+    // segments are moved back-to-front. Segments moving towards the start can only overwrite
+    // preceding segments, which have already been moved by then.
+    let mut k = 0;
+    // The position the replacement of edit `k` is to be written to.
+    let mut dest = edits[0].0.start;
+
+    while k < edits.len() {
+        let seg_dest = dest + edits[k].1.len();
+
+        if seg_dest > seg_start(k) {
+            // Find the end of the run of segments moving towards the end.
+            let mut run_end = k;
+            let mut run_dest = seg_dest;
+
+            while let Some(next) = edits.get(run_end + 1) {
+                let next_dest = run_dest + seg_len(run_end) + next.1.len();
+                if next_dest <= seg_start(run_end + 1) { break; }
+
+                run_end += 1;
+                run_dest = next_dest;
+            }
+
+            dest = run_dest + seg_len(run_end);
+
+            let mut j = run_end;
+            loop {
+                unsafe {
+                    ptr::copy(ptr.add(seg_start(j)), ptr.add(run_dest), seg_len(j));
+                }
+
+                if j == k { break; }
+                run_dest -= edits[j].1.len() + seg_len(j - 1);
+                j -= 1;
+            }
+
+            k = run_end + 1;
+        } else {
+            if seg_dest != seg_start(k) {
+                unsafe {
+                    ptr::copy(ptr.add(seg_start(k)), ptr.add(seg_dest), seg_len(k));
+                }
+            }
+
+            dest = seg_dest + seg_len(k);
+            k += 1;
+        }
+    }
+
+    // Copy the replacements into the gaps left between the kept segments.
+    let mut dest = edits[0].0.start;
+
+    for (k, &(_, replacement)) in edits.iter().enumerate() {
+        unsafe {
+            ptr::copy_nonoverlapping(replacement.as_ptr(), ptr.add(dest), replacement.len());
+        }
+        dest += replacement.len() + seg_len(k);
+    }
+
+    // Set the len *after* having initialized the elements.
+    unsafe {
+        vec.set_len(new_len);
+    }
+
+    Ok(())
+}
+
+/// Append `len` elements to `vec`, copied one after the other from `src_idx` onwards, as
+/// LZ77-style back-references do.
+///
+/// If `len` is greater than the distance `vec.len() - src_idx`, the elements from `src_idx`
+/// to the old end of `vec` are repeated. See [`copy_repeat()`](fn.copy_repeat.html).
+///
+/// ### Panics
+/// * If `src_idx` is out of bounds.
+/// * If the new capacity overflows.
+#[cfg(feature = "alloc")]
+pub fn extend_repeat<T: Copy>(vec: &mut Vec<T>, src_idx: usize, len: usize) {
+    unwrap_check!(extend_repeat_impl(vec, src_idx, len, Infallible));
+}
 
-    idx_check!(slice, src_idx);
-    idx_check!(slice, dest_idx);
-    len_check!(slice, src_idx, len);
-    len_check!(slice, dest_idx, len);
+/// Append `len` elements to `vec`, copied one after the other from `src_idx` onwards, as
+/// LZ77-style back-references do.
+///
+/// Non-panicking version of [`extend_repeat()`](fn.extend_repeat.html).
+///
+/// ### Errors
+/// Returns an error under the same conditions that `extend_repeat()` panics, or if allocating the
+/// new capacity fails. The `Vec` is not modified in that case.
+///
+/// Without the `try_reserve` feature, allocation failure aborts the process instead.
+#[cfg(feature = "alloc")]
+pub fn try_extend_repeat<T: Copy>(vec: &mut Vec<T>, src_idx: usize, len: usize)
+    -> Result<(), Error> {
+    extend_repeat_impl(vec, src_idx, len, Fallible)
+}
 
-    // At any point a Rust reference exists, the compiler is free to do this.
-    // So we explicitely add it to be caught by miri.
-    #[cfg(miri)]
-    slice.iter().copied().for_each(drop);
+#[cfg(feature = "alloc")]
+fn extend_repeat_impl<T: Copy>(vec: &mut Vec<T>, src_idx: usize, len: usize,
+                               fallibility: Fallibility) -> Result<(), Error> {
+    idx_check!(vec, src_idx, len, SrcOutOfBounds);
 
-    let ptr = slice.as_mut_ptr();
+    let old_len = vec.len();
+    reserve(vec, len, fallibility)?;
 
     unsafe {
-        ptr::copy(ptr.offset(src_idx as isize), ptr.offset(dest_idx as isize), len);
+        copy_repeat_raw(vec.as_mut_ptr(), src_idx, old_len, len);
+        // Set the len *after* having initialized the elements.
+        vec.set_len(old_len + len);
     }
+
+    Ok(())
 }
 
-/// Safe wrapper for `std::ptr::write_bytes()`/`memset()`.
-pub fn write_bytes(slice: &mut [u8], byte: u8) {
-    unsafe {
-        ptr::write_bytes(slice.as_mut_ptr(), byte, slice.len());
-    }
+/// Whether running out of capacity or memory is reported as an error or handled the way
+/// `Vec::reserve()` does, i.e. by panicking and aborting respectively.
+#[cfg(feature = "alloc")]
+#[derive(Copy, Clone)]
+enum Fallibility {
+    Fallible,
+    Infallible,
 }
 
-/// Prepend `elems` to `vec`, resizing if necessary.
+#[cfg(feature = "alloc")]
+use Fallibility::*;
+
+/// Reserve capacity for at least `additional` more elements in `vec`.
 ///
-/// ### Panics
+/// If `fallibility` is `Fallible`, capacity overflow is returned as an error, as is allocation
+/// failure with the `try_reserve` feature; otherwise this never returns an error.
+#[cfg(feature = "alloc")]
+fn reserve<T>(vec: &mut Vec<T>, additional: usize, fallibility: Fallibility)
+    -> Result<(), Error> {
+    match fallibility {
+        #[cfg(feature = "try_reserve")]
+        Fallible => {
+            check_capacity::<T>(vec.len(), additional)?;
+            vec.try_reserve(additional)
+                .map_err(|_| Error::AllocFailed { len: vec.len(), additional })
+        }
+        #[cfg(not(feature = "try_reserve"))]
+        Fallible => {
+            check_capacity::<T>(vec.len(), additional)?;
+            vec.reserve(additional);
+            Ok(())
+        }
+        Infallible => {
+            vec.reserve(additional);
+            Ok(())
+        }
+    }
+}
+
+/// Check that a collection of `len + additional` elements of `T` would not overflow `usize` or
+/// exceed `isize::MAX` bytes, the limits of `std::alloc::Layout`.
 ///
-/// If `vec.len() + elems.len()` overflows.
-#[cfg(feature = "std")]
-pub fn prepend<T: Copy>(elems: &[T], vec: &mut Vec<T>) {
-    let elems_len = elems.len(); // `<= isize::MAX as usize`
-    if elems_len == 0 { return; }
+/// `Vec::try_reserve()` reports both cases as `TryReserveError`, but what kind of error it was is
+/// not exposed on stable, so we check for overflow ourselves beforehand.
+#[cfg(feature = "alloc")]
+fn check_capacity<T>(len: usize, additional: usize) -> Result<(), Error> {
+    let overflow = Error::CapacityOverflow { len, additional };
+    let new_len = len.checked_add(additional).ok_or(overflow)?;
 
-    let old_len = vec.len(); // `<= isize::MAX as usize`
-    if old_len == 0 {
-        // Prepend = append: delegate to Rust's stdlib implementation.
-        vec.extend_from_slice(elems);
-    } else {
-        // Our overflow check occurs here, no need to do it ourselves.
-        vec.reserve(elems_len);
-        let ptr = vec.as_mut_ptr();
-        unsafe {
-            // Move the old elements down to the end.
-            ptr::copy(
-                ptr,
-                ptr.offset(elems_len as isize),
-                old_len,
-            );
-            // Copy the input elements to the start
-            ptr::copy_nonoverlapping(
-                elems.as_ptr(),
-                ptr,
-                elems_len,
-            );
-            // Set the len *after* having initialized the elements.
-            vec.set_len(old_len + elems_len);
-        }
+    // `isize::MAX`, without the associated constant which needs Rust 1.43.
+    let max_bytes = (!0 >> 1) - (mem::align_of::<T>() - 1);
+    match new_len.checked_mul(mem::size_of::<T>()) {
+        Some(bytes) if bytes <= max_bytes => Ok(()),
+        _ => Err(overflow),
     }
 }
 
@@ -109,6 +1385,78 @@ mod tests {
         copy_over(&mut arr, 2, 1, 7);
     }
 
+    #[test]
+    fn try_bounds_check() {
+        let mut arr = [0i32, 1, 2, 3, 4, 5];
+
+        assert_eq!(
+            try_copy_over(&mut arr, 2, 1, 7),
+            Err(Error::SrcOutOfBounds { idx: 2, len: 7, slice_len: 6 })
+        );
+        assert_eq!(
+            try_copy_over(&mut arr, 1, 6, 0),
+            Err(Error::DestOutOfBounds { idx: 6, len: 0, slice_len: 6 })
+        );
+        assert_eq!(
+            try_copy_over(&mut arr, 1, 2, !0),
+            Err(Error::Overflow { start: 1, len: !0 })
+        );
+        assert_eq!(arr, [0, 1, 2, 3, 4, 5]);
+
+        assert_eq!(try_copy_over(&mut arr, 0, 2, 4), Ok(()));
+        assert_eq!(arr, [0, 1, 0, 1, 2, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Length 7 starting at 2 is out of bounds (slice len 6).")]
+    fn bounds_check_message() {
+        let mut arr = [0i32, 1, 2, 3, 4, 5];
+
+        copy_over(&mut arr, 2, 1, 7);
+    }
+
+    #[test]
+    fn copy_within_ranges() {
+        let mut arr = [0i32, 1, 2, 3, 4, 5];
+
+        copy_within(&mut arr, 1..3, 0);
+        assert_eq!(arr, [1, 2, 2, 3, 4, 5]);
+
+        copy_within(&mut arr, 3..=5, 2);
+        assert_eq!(arr, [1, 2, 3, 4, 5, 5]);
+
+        copy_within(&mut arr, ..2, 4);
+        assert_eq!(arr, [1, 2, 3, 4, 1, 2]);
+
+        copy_within(&mut arr, 2.., 0);
+        assert_eq!(arr, [3, 4, 1, 2, 1, 2]);
+
+        copy_within(&mut arr, .., 0);
+        assert_eq!(arr, [3, 4, 1, 2, 1, 2]);
+    }
+
+    #[test]
+    fn try_copy_within_errors() {
+        let mut arr = [0i32, 1, 2, 3, 4, 5];
+
+        assert_eq!(
+            try_copy_within(&mut arr, 4..=6, 0),
+            Err(Error::SrcOutOfBounds { idx: 4, len: 3, slice_len: 6 })
+        );
+        assert_eq!(
+            try_copy_within(&mut arr, 2..4, 5),
+            Err(Error::DestOutOfBounds { idx: 5, len: 2, slice_len: 6 })
+        );
+        assert_eq!(
+            try_copy_within(&mut arr, 0..=!0, 0),
+            Err(Error::Overflow { start: !0, len: 1 })
+        );
+        #[allow(clippy::reversed_empty_ranges)]
+        let res = try_copy_within(&mut arr, 4..2, 0);
+        assert_eq!(res, Err(Error::BadRange { start: 4, end: 2 }));
+        assert_eq!(arr, [0, 1, 2, 3, 4, 5]);
+    }
+
     #[test]
     fn copy_empty() {
         let mut arr: [i32; 0] = [];
@@ -117,14 +1465,14 @@ mod tests {
     }
 
     #[test]
-    #[cfg(feature = "std")]
+    #[cfg(feature = "alloc")]
     fn prepend_empty() {
         let mut vec: Vec<i32> = vec![];
         prepend(&[1, 2, 3], &mut vec);
     }
 
     #[test]
-    #[cfg(feature = "std")]
+    #[cfg(feature = "alloc")]
     fn prepend_i32() {
         let mut vec = vec![3, 4, 5];
         prepend(&[1, 2], &mut vec);
@@ -134,10 +1482,580 @@ mod tests {
     /// Detect potential uninit values when running miri
     #[test]
     #[cfg(all(
-        feature = "std",
+        feature = "alloc",
         miri,
     ))]
     fn prepend_bool() {
         prepend(&[true], &mut vec![false]);
     }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn insert_slice_i32() {
+        let mut vec = vec![1, 4, 5];
+        insert_slice(&mut vec, 1, &[2, 3]);
+        assert_eq!(vec, &[1, 2, 3, 4, 5]);
+
+        insert_slice(&mut vec, 5, &[6]);
+        assert_eq!(vec, &[1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn try_insert_slice_bounds() {
+        let mut vec = vec![1, 2, 3];
+        assert_eq!(
+            try_insert_slice(&mut vec, 4, &[4]),
+            Err(Error::DestOutOfBounds { idx: 4, len: 1, slice_len: 3 })
+        );
+        assert_eq!(vec, &[1, 2, 3]);
+    }
+
+    /// Detect potential uninit values when running miri
+    #[test]
+    #[cfg(all(
+        feature = "alloc",
+        miri,
+    ))]
+    fn insert_slice_bool() {
+        insert_slice(&mut vec![false, false], 1, &[true, true]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn remove_range_i32() {
+        let mut vec = vec![1, 2, 3, 4, 5, 6];
+        remove_range(&mut vec, 1..3);
+        assert_eq!(vec, &[1, 4, 5, 6]);
+
+        remove_range(&mut vec, 2..);
+        assert_eq!(vec, &[1, 4]);
+
+        remove_range(&mut vec, 2..2);
+        assert_eq!(vec, &[1, 4]);
+
+        assert_eq!(
+            try_remove_range(&mut vec, 1..=2),
+            Err(Error::SrcOutOfBounds { idx: 1, len: 2, slice_len: 2 })
+        );
+        assert_eq!(vec, &[1, 4]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn truncate_front_i32() {
+        let mut vec = vec![1, 2, 3, 4, 5];
+        truncate_front(&mut vec, 2);
+        assert_eq!(vec, &[3, 4, 5]);
+
+        assert_eq!(
+            try_truncate_front(&mut vec, 4),
+            Err(Error::SrcOutOfBounds { idx: 0, len: 4, slice_len: 3 })
+        );
+
+        truncate_front(&mut vec, 3);
+        assert!(vec.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn replace_range_i32() {
+        let mut vec = vec![1, 2, 3, 4, 5];
+        // Grow
+        replace_range(&mut vec, 1..2, &[6, 7, 8]);
+        assert_eq!(vec, &[1, 6, 7, 8, 3, 4, 5]);
+        // Shrink
+        replace_range(&mut vec, 1..4, &[2]);
+        assert_eq!(vec, &[1, 2, 3, 4, 5]);
+        // Same size
+        replace_range(&mut vec, 3.., &[9, 9]);
+        assert_eq!(vec, &[1, 2, 3, 9, 9]);
+
+        assert_eq!(
+            try_replace_range(&mut vec, 4..6, &[]),
+            Err(Error::DestOutOfBounds { idx: 4, len: 2, slice_len: 5 })
+        );
+        assert_eq!(vec, &[1, 2, 3, 9, 9]);
+    }
+
+    /// Detect potential uninit values when running miri
+    #[test]
+    #[cfg(all(
+        feature = "alloc",
+        miri,
+    ))]
+    fn replace_range_bool() {
+        replace_range(&mut vec![false, false], ..1, &[true, true]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn apply_edits_i32() {
+        let mut vec: Vec<i32> = (0 .. 10).collect();
+        apply_edits(&mut vec, &[
+            (0 .. 0, &[-1, -2]),
+            (1 .. 3, &[]),
+            (3 .. 4, &[30, 31, 32]),
+            (4 .. 4, &[40]),
+            (5 .. 8, &[50]),
+            (10 .. 10, &[100, 101]),
+        ]);
+        assert_eq!(vec, &[-1, -2, 0, 30, 31, 32, 40, 4, 50, 8, 9, 100, 101]);
+
+        apply_edits(&mut vec, &[]);
+        assert_eq!(vec, &[-1, -2, 0, 30, 31, 32, 40, 4, 50, 8, 9, 100, 101]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn apply_edits_matches_splice() {
+        let edit_sets: &[&[(Range<usize>, &[u8])]] = &[
+            &[(1 .. 2, b"abc"), (2 .. 3, b"defg"), (7 .. 8, b"")],
+            &[(0 .. 4, b""), (4 .. 5, b"xyzxyz"), (6 .. 6, b"q"), (9 .. 12, b"rs")],
+            &[(2 .. 2, b"aaaa"), (3 .. 9, b"b"), (10 .. 11, b"cccc")],
+            &[(0 .. 12, b"z")],
+        ];
+
+        for edits in edit_sets {
+            let mut vec = b"0123456789AB".to_vec();
+            let mut expected = vec.clone();
+
+            for &(ref range, replacement) in edits.iter().rev() {
+                expected.splice(range.clone(), replacement.iter().cloned());
+            }
+
+            apply_edits(&mut vec, edits);
+            assert_eq!(vec, expected);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn try_apply_edits_errors() {
+        let mut vec = vec![1, 2, 3, 4, 5];
+        assert_eq!(
+            try_apply_edits(&mut vec, &[(0 .. 2, &[]), (1 .. 3, &[])]),
+            Err(Error::Overlap { first: 0, second: 1 })
+        );
+        assert_eq!(
+            try_apply_edits(&mut vec, &[(3 .. 4, &[]), (0 .. 1, &[])]),
+            Err(Error::Overlap { first: 3, second: 0 })
+        );
+        assert_eq!(
+            try_apply_edits(&mut vec, &[(3 .. 6, &[])]),
+            Err(Error::DestOutOfBounds { idx: 3, len: 3, slice_len: 5 })
+        );
+        assert_eq!(vec, &[1, 2, 3, 4, 5]);
+    }
+
+    /// Detect potential uninit values when running miri
+    #[test]
+    #[cfg(all(
+        feature = "alloc",
+        miri,
+    ))]
+    fn apply_edits_bool() {
+        apply_edits(&mut vec![false, false, false], &[(0 .. 1, &[true, true]), (2 .. 2, &[true])]);
+    }
+
+    #[test]
+    fn fill_values() {
+        let mut arr = [0u32; 13];
+        fill(&mut arr, 0xDEADBEEF);
+        assert!(arr.iter().all(|&x| x == 0xDEADBEEF));
+
+        let mut arr = [(0u8, 0u16); 5];
+        fill(&mut arr, (1, 2));
+        assert_eq!(arr, [(1, 2); 5]);
+
+        let mut arr: [f32; 0] = [];
+        fill(&mut arr, 1.0);
+    }
+
+    #[test]
+    fn fill_pod_values() {
+        let mut words = [1u32; 5];
+        fill_pod(&mut words, !0);
+        assert_eq!(words, [!0; 5]);
+        fill_pod(&mut words[1 ..], 0x0102_0304);
+        assert_eq!(words, [!0, 0x0102_0304, 0x0102_0304, 0x0102_0304, 0x0102_0304]);
+
+        let mut floats = [1.5f32; 3];
+        fill_pod(&mut floats, 0.0);
+        assert_eq!(floats, [0.0; 3]);
+
+        fill_pod(&mut [(); 2], ());
+        fill_pod(&mut [0u64; 0], 7);
+    }
+
+    #[test]
+    fn fill_range_values() {
+        let mut arr = [0i32; 6];
+        fill_range(&mut arr, 1..4, 7);
+        assert_eq!(arr, [0, 7, 7, 7, 0, 0]);
+
+        fill_range(&mut arr, 4.., -1);
+        assert_eq!(arr, [0, 7, 7, 7, -1, -1]);
+
+        assert_eq!(
+            try_fill_range(&mut arr, 5..=6, 1),
+            Err(Error::DestOutOfBounds { idx: 5, len: 2, slice_len: 6 })
+        );
+        assert_eq!(arr, [0, 7, 7, 7, -1, -1]);
+    }
+
+    #[test]
+    fn fill_pattern_values() {
+        let mut arr = [0u8; 11];
+        fill_pattern(&mut arr, &[0xDE, 0xAD, 0xBE, 0xEF]);
+        assert_eq!(arr, [0xDE, 0xAD, 0xBE, 0xEF, 0xDE, 0xAD, 0xBE, 0xEF, 0xDE, 0xAD, 0xBE]);
+
+        let mut arr = [0u32; 2];
+        fill_pattern(&mut arr, &[1, 2, 3]);
+        assert_eq!(arr, [1, 2]);
+
+        let mut arr: [u32; 0] = [];
+        fill_pattern(&mut arr, &[]);
+    }
+
+    #[test]
+    #[should_panic(expected = "empty pattern")]
+    fn fill_pattern_empty() {
+        fill_pattern(&mut [0u8; 4], &[]);
+    }
+
+    #[test]
+    fn copy_repeat_distances() {
+        let mut arr = [1u8, 2, 3, 0, 0, 0, 0, 0, 0, 0];
+        // distance 3 < len
+        copy_repeat(&mut arr, 0, 3, 7);
+        assert_eq!(arr, [1, 2, 3, 1, 2, 3, 1, 2, 3, 1]);
+
+        // distance 1
+        copy_repeat(&mut arr, 1, 2, 8);
+        assert_eq!(arr, [1, 2, 2, 2, 2, 2, 2, 2, 2, 2]);
+
+        // distance >= len
+        let mut arr = [1u8, 2, 3, 4, 5, 6];
+        copy_repeat(&mut arr, 0, 3, 3);
+        assert_eq!(arr, [1, 2, 3, 1, 2, 3]);
+
+        // backwards
+        copy_repeat(&mut arr, 3, 1, 3);
+        assert_eq!(arr, [1, 1, 2, 3, 2, 3]);
+
+        assert_eq!(
+            try_copy_repeat(&mut arr, 0, 2, 5),
+            Err(Error::DestOutOfBounds { idx: 2, len: 5, slice_len: 6 })
+        );
+
+        // distance 1 with elements wider than a byte
+        let mut arr = [1u16, 0x0203, 0, 0];
+        copy_repeat(&mut arr, 1, 2, 2);
+        assert_eq!(arr, [1, 0x0203, 0x0203, 0x0203]);
+
+        // empty, like `copy_over()`
+        assert_eq!(try_copy_repeat(&mut [0u8; 0], 0, 1, 0), Ok(()));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn extend_repeat_i32() {
+        let mut vec = vec![1, 2, 3];
+        extend_repeat(&mut vec, 1, 5);
+        assert_eq!(vec, &[1, 2, 3, 2, 3, 2, 3, 2]);
+
+        extend_repeat(&mut vec, 0, 2);
+        assert_eq!(vec, &[1, 2, 3, 2, 3, 2, 3, 2, 1, 2]);
+
+        assert_eq!(
+            try_extend_repeat(&mut vec, 10, 1),
+            Err(Error::SrcOutOfBounds { idx: 10, len: 1, slice_len: 10 })
+        );
+        assert_eq!(vec.len(), 10);
+    }
+
+    /// Detect potential uninit values when running miri
+    #[test]
+    #[cfg(all(
+        feature = "alloc",
+        miri,
+    ))]
+    fn extend_repeat_bool() {
+        extend_repeat(&mut vec![false, true], 0, 7);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn try_prepend_capacity() {
+        let mut vec = vec![1u32, 2];
+        assert_eq!(try_prepend(&[0], &mut vec), Ok(()));
+        assert_eq!(vec, &[0, 1, 2]);
+
+        let mut vec = vec![(); 2];
+        assert_eq!(try_prepend(&[(); 3], &mut vec), Ok(()));
+        // Any non-null, aligned pointer is valid for a slice of zero-sized elements.
+        let elems: &[()] = unsafe {
+            slice::from_raw_parts(ptr::NonNull::dangling().as_ptr(), !0 - 2)
+        };
+        assert_eq!(
+            try_prepend(elems, &mut vec),
+            Err(Error::CapacityOverflow { len: 5, additional: !0 - 2 })
+        );
+        assert_eq!(vec.len(), 5);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn try_extend_repeat_capacity() {
+        let mut vec = vec![(); 5];
+        assert_eq!(
+            try_extend_repeat(&mut vec, 0, !0),
+            Err(Error::CapacityOverflow { len: 5, additional: !0 })
+        );
+
+        let mut vec = vec![0u64];
+        assert_eq!(
+            try_extend_repeat(&mut vec, 0, !0 / 16),
+            Err(Error::CapacityOverflow { len: 1, additional: !0 / 16 })
+        );
+        assert_eq!(vec, &[0]);
+    }
+
+    /// On 32-bit targets the request may fit in the address space and actually succeed. Miri
+    /// treats allocation failure as resource exhaustion instead.
+    #[test]
+    #[cfg(all(
+        feature = "try_reserve",
+        target_pointer_width = "64",
+        not(miri),
+    ))]
+    fn try_extend_repeat_alloc_failed() {
+        let mut vec = vec![0u64];
+        assert_eq!(
+            try_extend_repeat(&mut vec, 0, isize::MAX as usize / 16),
+            Err(Error::AllocFailed { len: 1, additional: isize::MAX as usize / 16 })
+        );
+        assert_eq!(vec, &[0]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn prepend_with_i32() {
+        let mut vec = vec![3, 4, 5];
+        assert_eq!(prepend_with(&mut vec, 2, |w| w.extend_from_slice(&[1, 2])), 2);
+        assert_eq!(vec, &[1, 2, 3, 4, 5]);
+
+        assert_eq!(prepend_with(&mut vec, 4, |w| w.push(0)), 1);
+        assert_eq!(vec, &[0, 1, 2, 3, 4, 5]);
+
+        assert_eq!(prepend_with(&mut vec, 3, |_| ()), 0);
+        assert_eq!(vec, &[0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn prepend_with_panic() {
+        let mut vec = vec![1, 2, 3];
+        let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            prepend_with(&mut vec, 2, |w| {
+                w.push(0);
+                panic!("oops");
+            });
+        }));
+        assert!(res.is_err());
+        assert_eq!(vec, &[1, 2, 3]);
+    }
+
+    /// Detect potential uninit values when running miri
+    #[test]
+    #[cfg(all(
+        feature = "alloc",
+        miri,
+    ))]
+    fn prepend_with_bool() {
+        let mut vec = vec![false, false];
+        prepend_with(&mut vec, 3, |w| w.extend_from_slice(&[true, true]));
+        vec.iter().copied().for_each(drop);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn open_close_gap() {
+        let mut vec = vec![1, 5];
+        {
+            let gap = open_gap(&mut vec, 1, 3, 0);
+            assert_eq!(gap, &[0, 0, 0]);
+            gap.copy_from_slice(&[2, 3, 4]);
+        }
+        assert_eq!(vec, &[1, 2, 3, 4, 5]);
+
+        assert_eq!(open_gap(&mut vec, 5, 1, 6), &[6]);
+        assert_eq!(open_gap(&mut vec, 0, 0, 6), &[]);
+
+        close_gap(&mut vec, 1, 2);
+        assert_eq!(vec, &[1, 4, 5, 6]);
+
+        assert_eq!(
+            try_open_gap(&mut vec, 5, 1, 0),
+            Err(Error::DestOutOfBounds { idx: 5, len: 1, slice_len: 4 })
+        );
+        assert_eq!(
+            try_close_gap(&mut vec, 3, 2),
+            Err(Error::SrcOutOfBounds { idx: 3, len: 2, slice_len: 4 })
+        );
+        assert_eq!(vec, &[1, 4, 5, 6]);
+    }
+
+    /// Detect potential uninit values when running miri
+    #[test]
+    #[cfg(all(
+        feature = "alloc",
+        miri,
+    ))]
+    fn open_gap_bool() {
+        let mut vec = vec![false];
+        open_gap(&mut vec, 0, 3, true);
+        vec.iter().copied().for_each(drop);
+    }
+
+    #[test]
+    fn swap_ranges_i32() {
+        let mut arr = [0i32, 1, 2, 3, 4, 5, 6];
+        swap_ranges(&mut arr, 0, 4, 3);
+        assert_eq!(arr, [4, 5, 6, 3, 0, 1, 2]);
+
+        swap_ranges(&mut arr, 3, 2, 1);
+        assert_eq!(arr, [4, 5, 3, 6, 0, 1, 2]);
+
+        assert_eq!(
+            try_swap_ranges(&mut arr, 0, 2, 3),
+            Err(Error::Overlap { first: 0, second: 2 })
+        );
+        assert_eq!(
+            try_swap_ranges(&mut arr, 5, 0, 3),
+            Err(Error::SrcOutOfBounds { idx: 5, len: 3, slice_len: 7 })
+        );
+        assert_eq!(arr, [4, 5, 3, 6, 0, 1, 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Range starting at 1 overlaps range starting at 1")]
+    fn swap_ranges_same() {
+        swap_ranges(&mut [0u8; 4], 1, 1, 2);
+    }
+
+    #[test]
+    fn rotate_range_i32() {
+        let mut arr = [0i32, 1, 2, 3, 4, 5, 6];
+        rotate_range(&mut arr, 1..6, 3);
+        assert_eq!(arr, [0, 3, 4, 5, 1, 2, 6]);
+
+        rotate_range(&mut arr, .., 7);
+        rotate_range(&mut arr, 2..2, 2);
+        assert_eq!(arr, [0, 3, 4, 5, 1, 2, 6]);
+
+        assert_eq!(try_rotate_range(&mut arr, 2..5, 1), Err(Error::BadRange { start: 2, end: 1 }));
+        assert_eq!(try_rotate_range(&mut arr, 2..5, 6), Err(Error::BadRange { start: 6, end: 5 }));
+        assert_eq!(
+            try_rotate_range(&mut arr, 5..8, 6),
+            Err(Error::DestOutOfBounds { idx: 5, len: 3, slice_len: 7 })
+        );
+        assert_eq!(arr, [0, 3, 4, 5, 1, 2, 6]);
+    }
+
+    #[test]
+    fn rotate_all_mids() {
+        let data = [1u16, 2, 3, 4, 5, 6, 7, 8, 9];
+
+        for len in 0 .. data.len() + 1 {
+            for mid in 0 .. len + 1 {
+                let mut expected = [0; 9];
+                expected[.. len].copy_from_slice(&data[.. len]);
+                expected[.. len].rotate_left(mid);
+
+                let mut arr = [0; 9];
+                arr[.. len].copy_from_slice(&data[.. len]);
+                rotate_range(&mut arr, .. len, mid);
+                assert_eq!(arr, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn move_range_i32() {
+        let mut arr = [0i32, 1, 2, 3, 4, 5, 6];
+        move_range(&mut arr, 4..6, 1);
+        assert_eq!(arr, [0, 4, 5, 1, 2, 3, 6]);
+
+        move_range(&mut arr, 1..3, 4);
+        assert_eq!(arr, [0, 1, 2, 3, 4, 5, 6]);
+
+        move_range(&mut arr, 2..=4, 2);
+        move_range(&mut arr, 7.., 0);
+        assert_eq!(arr, [0, 1, 2, 3, 4, 5, 6]);
+
+        assert_eq!(
+            try_move_range(&mut arr, 4..6, 6),
+            Err(Error::DestOutOfBounds { idx: 6, len: 2, slice_len: 7 })
+        );
+        assert_eq!(
+            try_move_range(&mut arr, 6..8, 0),
+            Err(Error::SrcOutOfBounds { idx: 6, len: 2, slice_len: 7 })
+        );
+        assert_eq!(arr, [0, 1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Range start 3 is greater than range end 2")]
+    fn rotate_range_bad_mid() {
+        rotate_range(&mut [0u8; 4], 0..2, 3);
+    }
+
+    /// Detect potential uninit values when running miri
+    #[test]
+    #[cfg(miri)]
+    fn rotate_range_bool() {
+        let mut arr = [false, true, true, false, true];
+        rotate_range(&mut arr, 1.., 2);
+        move_range(&mut arr, 0..2, 3);
+    }
+
+    #[test]
+    fn compare_mismatch() {
+        let a = b"the quick brown fox jumps";
+        let mut b = *a;
+        assert_eq!(mismatch(a, &b), None);
+        assert_eq!(compare(a, &b), Ordering::Equal);
+
+        for &idx in &[0, 7, 8, 9, 17, 24] {
+            b = *a;
+            b[idx] = b'~';
+            assert_eq!(mismatch(a, &b), Some(idx));
+            assert_eq!(compare(a, &b), Ordering::Less);
+            assert_eq!(compare(&b, a), Ordering::Greater);
+        }
+
+        assert_eq!(mismatch(&a[.. 20], a), Some(20));
+        assert_eq!(compare(&a[.. 20], a), Ordering::Less);
+        assert_eq!(compare(b"", b""), Ordering::Equal);
+    }
+
+    #[test]
+    fn compare_ranges_u8() {
+        let bytes = b"abcabdabc";
+        assert_eq!(compare_ranges(bytes, 0, 6, 3), Ordering::Equal);
+        assert_eq!(compare_ranges(bytes, 0, 3, 3), Ordering::Less);
+        assert_eq!(compare_ranges(bytes, 3, 6, 3), Ordering::Greater);
+        assert_eq!(compare_ranges(bytes, 1, 8, 0), Ordering::Equal);
+
+        assert_eq!(
+            try_compare_ranges(bytes, 9, 0, 1),
+            Err(Error::SrcOutOfBounds { idx: 9, len: 1, slice_len: 9 })
+        );
+        assert_eq!(
+            try_compare_ranges(bytes, 0, 7, 3),
+            Err(Error::DestOutOfBounds { idx: 7, len: 3, slice_len: 9 })
+        );
+    }
 }
@@ -0,0 +1,60 @@
+//! Marker traits for types with special memory properties.
+
+/// Marker trait for types for which the all-zero bit pattern is a valid value.
+///
+/// Implemented for arrays of `Zeroable` types of up to 32 elements and of the lengths 48, 64, 96,
+/// 128, 256, 512, 1024, 2048 and 4096.
+///
+/// ### Safety
+/// Implementors must guarantee that a value with every byte set to zero, including any padding,
+/// is a valid instance of the type.
+pub unsafe trait Zeroable {}
+
+macro_rules! impl_zeroable (
+    ($($ty:ty),*) => {
+        $(unsafe impl Zeroable for $ty {})*
+    }
+);
+
+/// Implement a marker trait for arrays of element types implementing it, for the lengths listed.
+macro_rules! impl_arrays (
+    ($trait_:ident: $($len:expr),*) => {
+        $(unsafe impl<T: $trait_> $trait_ for [T; $len] {})*
+    };
+);
+
+impl_zeroable!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64, bool, char, ());
+
+unsafe impl<T> Zeroable for *const T {}
+unsafe impl<T> Zeroable for *mut T {}
+
+for_array_lengths!(impl_arrays!(Zeroable:));
+
+/// Marker trait for "plain old data": types without padding for which every bit pattern is a
+/// valid value.
+///
+/// Like [`Zeroable`](trait.Zeroable.html), implemented for arrays of up to 32 elements and of the
+/// lengths 48, 64, 96, 128, 256, 512, 1024, 2048 and 4096.
+///
+/// Slices of `Pod` types can be viewed as bytes and vice versa with
+/// [`as_bytes()`](fn.as_bytes.html) and [`cast_slice()`](fn.cast_slice.html).
+///
+/// For structs, prefer `#[derive(Pod)]` from the `safemem-derive` crate over implementing it by
+/// hand, as it checks the requirements below at compile time.
+///
+/// ### Safety
+/// Implementors must guarantee that the type
+/// * has no padding bytes, so every byte of a value is initialized;
+/// * is valid for any bit pattern, unlike e.g. `bool` or `char`;
+/// * contains no pointers, references or interior mutability.
+pub unsafe trait Pod: Zeroable + Copy + 'static {}
+
+macro_rules! impl_pod (
+    ($($ty:ty),*) => {
+        $(unsafe impl Pod for $ty {})*
+    }
+);
+
+impl_pod!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64, ());
+
+for_array_lengths!(impl_arrays!(Pod:));
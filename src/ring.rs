@@ -0,0 +1,293 @@
+//! A fixed-capacity ring buffer over caller-provided storage.
+
+use std::marker::PhantomData;
+use std::{cmp, fmt};
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use copy_over;
+
+/// Storage for a [`RingBuffer`](struct.RingBuffer.html) which can be viewed as a slice of `T`.
+///
+/// Implemented for `&mut [T]`, for `Vec<T>` with the `alloc` feature, and for arrays of up to 32
+/// elements and of the lengths 48, 64, 96, 128, 256, 512, 1024, 2048 and 4096. Unlike
+/// `AsRef<[T]>`, this does not depend on the compiler version for arrays longer than 32.
+pub trait RingStorage<T> {
+    /// View the storage as a slice.
+    fn as_slice(&self) -> &[T];
+    /// View the storage as a mutable slice.
+    fn as_mut_slice(&mut self) -> &mut [T];
+}
+
+impl<T> RingStorage<T> for &mut [T] {
+    fn as_slice(&self) -> &[T] {
+        self
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> RingStorage<T> for Vec<T> {
+    fn as_slice(&self) -> &[T] {
+        self
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+macro_rules! impl_array_storage (
+    ($($len:expr),*) => {
+        $(impl<T> RingStorage<T> for [T; $len] {
+            fn as_slice(&self) -> &[T] {
+                self
+            }
+
+            fn as_mut_slice(&mut self) -> &mut [T] {
+                self
+            }
+        })*
+    }
+);
+
+for_array_lengths!(impl_array_storage!());
+
+/// A fixed-capacity FIFO ring buffer of `T: Copy` which never allocates.
+///
+/// The storage `S` is provided by the caller and may be anything implementing
+/// [`RingStorage`](trait.RingStorage.html), e.g. a `&mut [T]` or a `[T; N]`. Its initial
+/// contents are ignored.
+///
+/// Bulk writes and reads copy at most two contiguous runs of elements, one on either side of the
+/// point where the buffer wraps around.
+pub struct RingBuffer<T, S> {
+    storage: S,
+    /// The index of the first element in `storage`.
+    head: usize,
+    len: usize,
+    _marker: PhantomData<T>,
+}
+
+impl<T: Copy, S: RingStorage<T>> RingBuffer<T, S> {
+    /// Create an empty ring buffer using `storage`, with a capacity of `storage`'s length.
+    pub fn new(storage: S) -> Self {
+        RingBuffer {
+            storage,
+            head: 0,
+            len: 0,
+            _marker: PhantomData,
+        }
+    }
+
+    /// The maximum number of elements the buffer can hold.
+    pub fn capacity(&self) -> usize {
+        self.storage.as_slice().len()
+    }
+
+    /// The number of elements in the buffer.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// `true` if there are no elements in the buffer.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// `true` if no more elements can be written to the buffer.
+    pub fn is_full(&self) -> bool {
+        self.len == self.capacity()
+    }
+
+    /// The number of elements that can be written before the buffer is full.
+    pub fn free(&self) -> usize {
+        self.capacity() - self.len
+    }
+
+    /// Append as many elements from the start of `src` as fit, returning the number written.
+    pub fn write_slice(&mut self, src: &[T]) -> usize {
+        let count = cmp::min(src.len(), self.free());
+        if count == 0 { return 0; }
+
+        let cap = self.capacity();
+        let tail = (self.head + self.len) % cap;
+        let first_len = cmp::min(count, cap - tail);
+
+        let buf = self.storage.as_mut_slice();
+        buf[tail .. tail + first_len].copy_from_slice(&src[.. first_len]);
+        buf[.. count - first_len].copy_from_slice(&src[first_len .. count]);
+
+        self.len += count;
+        count
+    }
+
+    /// Copy as many elements from the front of the buffer as fit into `dest`, without removing
+    /// them, returning the number copied.
+    pub fn peek(&self, dest: &mut [T]) -> usize {
+        let count = cmp::min(dest.len(), self.len);
+
+        let (first, second) = self.as_slices();
+        let first_len = cmp::min(count, first.len());
+        dest[.. first_len].copy_from_slice(&first[.. first_len]);
+        dest[first_len .. count].copy_from_slice(&second[.. count - first_len]);
+
+        count
+    }
+
+    /// Move as many elements from the front of the buffer as fit into `dest`, returning the
+    /// number moved.
+    pub fn read_slice(&mut self, dest: &mut [T]) -> usize {
+        let count = self.peek(dest);
+        self.skip(count)
+    }
+
+    /// Remove up to `n` elements from the front of the buffer, returning the number removed.
+    pub fn skip(&mut self, n: usize) -> usize {
+        let count = cmp::min(n, self.len);
+        if count == 0 { return 0; }
+
+        self.head = (self.head + count) % self.capacity();
+        self.len -= count;
+        count
+    }
+
+    /// Remove all elements.
+    pub fn clear(&mut self) {
+        self.head = 0;
+        self.len = 0;
+    }
+
+    /// The contents of the buffer in order, as the part before the wrap-around point and the
+    /// part after it. The second slice is empty if the contents do not wrap around.
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let buf = self.storage.as_slice();
+        let first_len = cmp::min(self.len, buf.len() - self.head);
+        (&buf[self.head .. self.head + first_len], &buf[.. self.len - first_len])
+    }
+
+    /// Rearrange the contents of the buffer in place so they no longer wrap around, returning
+    /// them as a single slice.
+    ///
+    /// If the free space is large enough to hold either part of the contents, this is two
+    /// `memmove()`s; otherwise the storage is rotated in place.
+    pub fn make_contiguous(&mut self) -> &mut [T] {
+        let cap = self.capacity();
+        let first_len = cmp::min(self.len, cap - self.head);
+        let second_len = self.len - first_len;
+        let free = cap - self.len;
+
+        {
+            let buf = self.storage.as_mut_slice();
+
+            if second_len == 0 {
+                // Already contiguous.
+            } else if free >= first_len {
+                // from: [C D . . . . A B]
+                // to:   [A B C D . . . .]
+                copy_over(buf, 0, first_len, second_len);
+                copy_over(buf, self.head, 0, first_len);
+                self.head = 0;
+            } else if free >= second_len {
+                // from: [D E . . A B C]
+                // to:   [. . A B C D E]
+                copy_over(buf, self.head, second_len, first_len);
+                copy_over(buf, 0, second_len + first_len, second_len);
+                self.head = second_len;
+            } else {
+                // from: [D E F . A B C]
+                // to:   [A B C D E F .]
+                buf.rotate_left(self.head);
+                self.head = 0;
+            }
+        }
+
+        let head = self.head;
+        &mut self.storage.as_mut_slice()[head .. head + self.len]
+    }
+
+    /// Consume the buffer, returning the storage.
+    pub fn into_inner(self) -> S {
+        self.storage
+    }
+}
+
+impl<T: Copy + fmt::Debug, S: RingStorage<T>> fmt::Debug for RingBuffer<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (first, second) = self.as_slices();
+        f.debug_list().entries(first.iter().chain(second)).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_read_wrap() {
+        let mut ring = RingBuffer::new([0u8; 8]);
+        assert_eq!(ring.write_slice(b"abcdef"), 6);
+        assert_eq!(ring.skip(4), 4);
+        assert_eq!(ring.write_slice(b"ghijklmn"), 6);
+        assert!(ring.is_full());
+        assert_eq!(ring.as_slices(), (&b"efgh"[..], &b"ijkl"[..]));
+
+        let mut out = [0u8; 3];
+        assert_eq!(ring.peek(&mut out), 3);
+        assert_eq!(&out, b"efg");
+        assert_eq!(ring.read_slice(&mut out), 3);
+        assert_eq!(ring.read_slice(&mut out), 3);
+        assert_eq!(&out, b"hij");
+        assert_eq!(ring.read_slice(&mut out), 2);
+        assert_eq!(&out[..2], b"kl");
+        assert!(ring.is_empty());
+        assert_eq!(ring.skip(1), 0);
+    }
+
+    #[test]
+    fn borrowed_storage() {
+        let mut storage = [0i32; 4];
+        {
+            let mut ring = RingBuffer::new(&mut storage[..]);
+            assert_eq!(ring.write_slice(&[1, 2, 3, 4, 5]), 4);
+        }
+        assert_eq!(storage, [1, 2, 3, 4]);
+
+        let mut empty = RingBuffer::new([0u8; 0]);
+        assert_eq!(empty.write_slice(b"a"), 0);
+        assert_eq!(empty.make_contiguous(), b"");
+    }
+
+    #[test]
+    fn long_array_storage() {
+        let mut ring = RingBuffer::new([0u16; 64]);
+        assert_eq!(ring.capacity(), 64);
+        assert_eq!(ring.write_slice(&[7; 100]), 64);
+        assert_eq!(ring.skip(60), 60);
+        assert_eq!(ring.write_slice(&[1, 2]), 2);
+        assert_eq!(ring.make_contiguous(), &[7, 7, 7, 7, 1, 2]);
+    }
+
+    #[test]
+    fn make_contiguous_cases() {
+        let data = [1usize, 2, 3, 4, 5, 6, 7, 8];
+
+        // (storage len, elements skipped, elements written after skipping)
+
+        for &(cap, skip, write) in &[(8, 6, 4), (8, 3, 2), (7, 4, 5), (7, 4, 6), (5, 2, 3)] {
+            let mut storage = [0usize; 8];
+            let mut ring = RingBuffer::new(&mut storage[.. cap]);
+            ring.write_slice(&[0; 8][.. skip]);
+            ring.skip(skip);
+
+            let expected = &data[.. write];
+            ring.write_slice(expected);
+            assert_eq!(ring.make_contiguous(), expected);
+            assert_eq!(ring.as_slices(), (expected, &[][..]));
+        }
+    }
+}
@@ -0,0 +1,137 @@
+//! Wiping of sensitive data in a way the optimizer cannot elide.
+//!
+//! Plain writes to memory which is never read again, e.g. because it is about to be deallocated,
+//! are dead stores the compiler is free to remove. The functions in this module use volatile
+//! writes followed by a compiler fence instead, which are always performed.
+
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{self, Ordering};
+use std::{mem, ptr, slice};
+
+use marker::Zeroable;
+
+/// Set every byte of `slice` to `byte` in a way that cannot be optimized out.
+///
+/// Volatile version of [`write_bytes()`](fn.write_bytes.html).
+pub fn secure_write_bytes(slice: &mut [u8], byte: u8) {
+    for b in slice.iter_mut() {
+        unsafe {
+            ptr::write_volatile(b, byte);
+        }
+    }
+
+    atomic::compiler_fence(Ordering::SeqCst);
+}
+
+/// Set every byte of `slice`, including any padding, to zero in a way that cannot be optimized
+/// out.
+///
+/// To wipe a single value, pass `std::slice::from_mut(&mut value)`.
+pub fn secure_zero<T: Zeroable>(slice: &mut [T]) {
+    // Writing typed values would leave the padding bytes untouched, so write the bytes one by
+    // one. `T: Zeroable` guarantees the result is a valid value.
+    let ptr = slice.as_mut_ptr() as *mut u8;
+
+    for i in 0 .. mem::size_of_val(slice) {
+        unsafe {
+            ptr::write_volatile(ptr.add(i), 0);
+        }
+    }
+
+    atomic::compiler_fence(Ordering::SeqCst);
+}
+
+/// A wrapper which wipes its contents with [`secure_zero()`](fn.secure_zero.html) when dropped.
+///
+/// Note that this only wipes the memory the value occupies when it is dropped; any copies made
+/// beforehand, including by moving the wrapper, are not wiped.
+#[derive(Debug, Default)]
+pub struct Zeroizing<T: Zeroable>(T);
+
+impl<T: Zeroable> Zeroizing<T> {
+    /// Wrap `value` to be wiped when dropped.
+    pub fn new(value: T) -> Self {
+        Zeroizing(value)
+    }
+}
+
+impl<T: Zeroable> From<T> for Zeroizing<T> {
+    fn from(value: T) -> Self {
+        Zeroizing(value)
+    }
+}
+
+impl<T: Zeroable> Deref for Zeroizing<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: Zeroable> DerefMut for Zeroizing<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: Zeroable> Drop for Zeroizing<T> {
+    fn drop(&mut self) {
+        secure_zero(slice::from_mut(&mut self.0));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secure_write_bytes_all() {
+        let mut arr = [1u8; 7];
+        secure_write_bytes(&mut arr, 0xFF);
+        assert_eq!(arr, [0xFF; 7]);
+    }
+
+    #[test]
+    fn secure_zero_typed() {
+        let mut arr = [[1u64, 2], [3, 4]];
+        secure_zero(&mut arr);
+        assert_eq!(arr, [[0, 0], [0, 0]]);
+    }
+
+    #[test]
+    fn secure_zero_padding() {
+        #[derive(Clone, Copy)]
+        #[repr(C)]
+        struct Padded {
+            a: u8,
+            b: u32,
+        }
+
+        unsafe impl Zeroable for Padded {}
+
+        let mut arr = [Padded { a: 1, b: 2 }; 2];
+        let size = mem::size_of_val(&arr);
+
+        unsafe {
+            ptr::write_bytes(arr.as_mut_ptr() as *mut u8, 0xAA, size);
+        }
+        secure_zero(&mut arr);
+
+        let bytes = unsafe { slice::from_raw_parts(arr.as_ptr() as *const u8, size) };
+        assert_eq!(bytes, &[0; 16][..]);
+    }
+
+    #[test]
+    fn zeroizing_drop() {
+        // Drop the wrapper manually to be able to inspect its memory afterwards.
+        let mut key = mem::ManuallyDrop::new(Zeroizing::new([0xAAu8; 32]));
+        key[0] = 0;
+        assert_eq!(key[..2], [0, 0xAA]);
+
+        unsafe {
+            mem::ManuallyDrop::drop(&mut key);
+        }
+        assert_eq!(key.0, [0; 32]);
+    }
+}
@@ -0,0 +1,98 @@
+//! Safe initialization of uninitialized memory.
+
+use std::mem::MaybeUninit;
+use std::ptr;
+
+/// A write-only view of a run of uninitialized elements which keeps track of how many of them
+/// have been initialized, front to back.
+///
+/// Used by [`prepend_with()`](fn.prepend_with.html) to let a closure write elements directly
+/// into a `Vec`'s spare capacity. As the elements cannot be read back and only the initialized
+/// prefix is ever exposed, no uninitialized value can leak.
+pub struct UninitWriter<'a, T: 'a> {
+    buf: &'a mut [MaybeUninit<T>],
+    len: usize,
+}
+
+impl<'a, T: Copy> UninitWriter<'a, T> {
+    pub(crate) fn new(buf: &'a mut [MaybeUninit<T>]) -> Self {
+        UninitWriter { buf, len: 0 }
+    }
+
+    /// The total number of elements that can be written.
+    pub fn capacity(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// The number of elements written so far.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// `true` if no elements have been written yet.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of elements that can still be written.
+    pub fn remaining(&self) -> usize {
+        self.buf.len() - self.len
+    }
+
+    /// Write `value` after the elements written so far.
+    ///
+    /// ### Panics
+    /// If there is no room left.
+    pub fn push(&mut self, value: T) {
+        assert!(self.remaining() > 0, "UninitWriter is full (capacity {})", self.capacity());
+
+        self.buf[self.len] = MaybeUninit::new(value);
+        self.len += 1;
+    }
+
+    /// Copy `elems` after the elements written so far.
+    ///
+    /// ### Panics
+    /// If `elems.len() > self.remaining()`.
+    pub fn extend_from_slice(&mut self, elems: &[T]) {
+        assert!(elems.len() <= self.remaining(),
+                "Cannot write {} elements to UninitWriter with {} remaining",
+                elems.len(), self.remaining());
+
+        unsafe {
+            ptr::copy_nonoverlapping(
+                elems.as_ptr(),
+                self.buf.as_mut_ptr().add(self.len) as *mut T,
+                elems.len(),
+            );
+        }
+        self.len += elems.len();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_elements() {
+        let mut buf = [MaybeUninit::<u8>::uninit(); 4];
+        let mut writer = UninitWriter::new(&mut buf);
+        assert!(writer.is_empty());
+
+        writer.push(1);
+        writer.extend_from_slice(&[2, 3]);
+        assert_eq!(writer.len(), 3);
+        assert_eq!(writer.remaining(), 1);
+        assert_eq!(writer.capacity(), 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "full")]
+    fn push_full() {
+        let mut buf = [MaybeUninit::<u8>::uninit(); 1];
+        let mut writer = UninitWriter::new(&mut buf);
+        writer.push(1);
+        writer.push(2);
+    }
+}